@@ -2,7 +2,7 @@ use std::{ffi::CString, ptr};
 
 use gl::types::*;
 use sb7::gl;
-use sb7::application::{Application};
+use sb7::application::{Application, AppContext};
 
 #[derive(Default)]
 struct MyApplication {
@@ -97,7 +97,7 @@ impl MyApplication {
 }
 
 impl Application for MyApplication {
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     self.rendering_program = self.compile_shaders();
     gl! {
       gl::CreateVertexArrays(1, &mut self.vertex_array_object);
@@ -105,7 +105,7 @@ impl Application for MyApplication {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, current_time: f64) {
     gl! {
       let g = (current_time as f32).sin() * 0.5 + 0.5;
       gl::ClearBufferfv(gl::COLOR, 0, &[g, g, g, 1.0f32] as *const f32);
@@ -115,7 +115,7 @@ impl Application for MyApplication {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       gl::DeleteVertexArrays(1, &self.vertex_array_object);
       gl::DeleteProgram(self.rendering_program);
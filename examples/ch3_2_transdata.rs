@@ -17,7 +17,7 @@ impl application::Application for MyApp {
     }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut application::AppContext) {
     let vs_source = CString::new(
       "
         #version 460 core
@@ -83,7 +83,7 @@ impl application::Application for MyApp {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, _context: &mut application::AppContext, current_time: f64) {
     let current_time = current_time as f32;
     let green = [0.0, 0.0, 0.0, 0.0f32];
     let attrib = [current_time.sin() * 0.5, current_time.cos() * 0.6, 0.0, 0.0];
@@ -103,7 +103,7 @@ impl application::Application for MyApp {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut application::AppContext) {
     sb7::gl! {
       gl::DeleteProgram(self.program);
       gl::DeleteVertexArrays(1, &self.vao);
@@ -1,7 +1,7 @@
 use std::ffi::CString;
 
 use gl::types::GLuint;
-use sb7::application::{AppConfig, Application};
+use sb7::application::{AppConfig, Application, AppContext};
 
 #[derive(Default)]
 struct MyApp {
@@ -17,7 +17,7 @@ impl Application for MyApp {
     }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     let vs_source = CString::new(
       "
           #version 460 core
@@ -84,7 +84,7 @@ impl Application for MyApp {
     }
   }
 
-  fn render(&mut self, _current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
     let color = [0.0, 0.0, 0.0, 0.0];
     sb7::gl! {
       gl::ClearBufferfv(gl::COLOR, 0, color.as_ptr());
@@ -92,7 +92,7 @@ impl Application for MyApp {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     sb7::gl! {
       gl::DeleteProgram(self.program);
       gl::DeleteVertexArrays(1, &self.vao);
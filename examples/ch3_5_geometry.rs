@@ -1,7 +1,7 @@
 use std::{ffi::CString, ptr::null};
 
 use gl::types::*;
-use sb7::application::{AppConfig, Application};
+use sb7::application::{AppConfig, Application, AppContext};
 
 #[derive(Default)]
 struct MyApplication {
@@ -17,7 +17,7 @@ impl Application for MyApplication {
     }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     let vs_source = CString::new(
       "
       #version 460 core
@@ -148,7 +148,7 @@ impl Application for MyApplication {
     }
   }
 
-  fn render(&mut self, _current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
     sb7::gl! {
       gl::ClearBufferfv(gl::COLOR, 0, [0.0, 0.25, 0.0, 1.0f32].as_ptr());
 
@@ -157,7 +157,7 @@ impl Application for MyApplication {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     sb7::gl! {
       gl::DeleteVertexArrays(1, &self.vao);
       gl::DeleteProgram(self.program);
@@ -41,7 +41,7 @@ impl Application for App {
                 ..Default::default() }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     let vs_src = "
       #version 460 core
 
@@ -134,7 +134,7 @@ impl Application for App {
     }
 
     gl! {
-      self.tex_alien_array = ktx::file::load("media/textures/aliens.ktx").unwrap().0;
+      self.tex_alien_array = ktx::file::load(&context.media_path("media/textures/aliens.ktx")).unwrap().0;
       BindTexture(TEXTURE_2D_ARRAY, self.tex_alien_array);
       TexParameteri(TEXTURE_2D_ARRAY, TEXTURE_MIN_FILTER, LINEAR_MIPMAP_LINEAR as _);
     }
@@ -162,7 +162,7 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, current_time: f64) {
     let t = current_time as f32;
 
     let droplet: *mut Vec4 = gl! {
@@ -194,7 +194,7 @@ impl Application for App {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       DeleteTextures(1, &self.tex_alien_array);
       DeleteBuffers(1, &self.rain_buffer);
@@ -43,7 +43,7 @@ struct App {
 }
 
 impl App {
-  fn load_shaders(&mut self) {
+  fn load_shaders(&mut self, context: &AppContext) {
     gl! {
       for prog in [self.clear_program, self.append_program, self.resolve_program] {
         DeleteProgram(prog);
@@ -51,21 +51,21 @@ impl App {
     }
 
     self.clear_program = program::link_from_shaders(&[
-      shader::load("media/shaders/fragmentlist/clear.vs.glsl", VERTEX_SHADER, true),
-      shader::load("media/shaders/fragmentlist/clear.fs.glsl", FRAGMENT_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/clear.vs.glsl"), VERTEX_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/clear.fs.glsl"), FRAGMENT_SHADER, true),
     ], true);
 
     self.append_program = program::link_from_shaders(&[
-      shader::load("media/shaders/fragmentlist/append.vs.glsl", VERTEX_SHADER, true),
-      shader::load("media/shaders/fragmentlist/append.fs.glsl", FRAGMENT_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/append.vs.glsl"), VERTEX_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/append.fs.glsl"), FRAGMENT_SHADER, true),
     ], true);
 
     let mvp = std::ffi::CString::new("mvp").unwrap();
     self.uniforms.mvp = gl! { GetUniformLocation(self.append_program, mvp.as_ptr()) };
 
     self.resolve_program = program::link_from_shaders(&[
-      shader::load("media/shaders/fragmentlist/resolve.vs.glsl", VERTEX_SHADER, true),
-      shader::load("media/shaders/fragmentlist/resolve.fs.glsl", FRAGMENT_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/resolve.vs.glsl"), VERTEX_SHADER, true),
+      shader::load(&context.media_path("media/shaders/fragmentlist/resolve.fs.glsl"), FRAGMENT_SHADER, true),
     ], true);
   }
 }
@@ -76,11 +76,11 @@ impl Application for App {
                 ..Default::default() }
   }
 
-  fn startup(&mut self) {
-    self.load_shaders();
+  fn startup(&mut self, context: &mut AppContext) {
+    self.load_shaders(context);
 
     gl! {
-      self.object.load("media/objects/dragon.sbm");
+      self.object.load(&context.media_path("media/objects/dragon.sbm"));
 
       GenBuffers(1, &mut self.fragment_buffer);
       BindBuffer(SHADER_STORAGE_BUFFER, self.fragment_buffer);
@@ -99,7 +99,7 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, context: &mut AppContext, current_time: f64) {
     let f = current_time as f32;
 
     gl! {
@@ -118,7 +118,7 @@ impl Application for App {
                                vec3!(0.0, 1.0, 0.0));
 
       let mv_matrix = view_matrix * model_matrix;
-      let AppConfig { width, height, .. } = self.info();
+      let AppConfig { width, height, .. } = context.info.clone();
       let proj_matrix = perspective(50.0, width as f32 / height as f32,
                                     0.1,
                                     1000.0);
@@ -150,15 +150,15 @@ impl Application for App {
     }
   }
 
-  fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+  fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
     if let glfw::Action::Press = press {
       if let glfw::Key::R = key {
-        self.load_shaders();
+        self.load_shaders(context);
       }
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       DeleteProgram(self.append_program);
       DeleteProgram(self.resolve_program);
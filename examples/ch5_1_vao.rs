@@ -1,5 +1,5 @@
 use gl::types::GLuint;
-use sb7::application::Application;
+use sb7::application::{Application, AppContext};
 use std::ffi::{c_void, CString};
 use std::mem::{size_of_val, size_of};
 use std::ptr::null;
@@ -12,7 +12,7 @@ struct App {
 }
 
 impl Application for App {
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     #[allow(dead_code)]
     struct Vertex {
       x: f32, y: f32, z: f32, // position
@@ -87,14 +87,14 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, _current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
     sb7::gl! {
       gl::ClearBufferfv(gl::COLOR,0, [0.0, 0.0, 0.0f32].as_ptr());
       gl::DrawArrays(gl::TRIANGLES, 0, 3);
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     sb7::gl! {
       gl::DeleteBuffers(2, &self.buf);
       gl::DeleteProgram(self.program);
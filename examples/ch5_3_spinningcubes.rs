@@ -1,5 +1,5 @@
 use gl::types::*;
-use sb7::application::{Application, AppConfig};
+use sb7::application::{Application, AppConfig, AppContext};
 use sb7::mat4;
 use sb7::vmath::{Mat4, translate, rotate_with_axis};
 use std::ffi::CString;
@@ -15,7 +15,7 @@ struct App {
 }
 
 impl Application for App {
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     #[rustfmt::skip]
     let vertex_position : &[f32]= &[
       -0.25,  0.25, -0.25,
@@ -134,10 +134,10 @@ impl Application for App {
     }
 
     let AppConfig { width, height, .. } = AppConfig::default();
-    self.on_resize(width as _, height as _);
+    self.on_resize(context, width as _, height as _);
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, current_time: f64) {
     sb7::gl! {
       gl::ClearBufferfv(gl::COLOR,0, [0.0, 0.0, 0.0].as_ptr());
       gl::ClearBufferfv(gl::DEPTH, 0, &1.0);
@@ -160,7 +160,7 @@ impl Application for App {
     }
   }
 
-  fn on_resize(&mut self, w: i32, h: i32) {
+  fn on_resize(&mut self, _context: &mut AppContext, w: i32, h: i32) {
     let aspect = w as GLfloat / h as GLfloat;
     self.proj_matrix = sb7::vmath::perspective(50.0, aspect, 0.1, 1000.0);
     sb7::gl! {
@@ -168,7 +168,7 @@ impl Application for App {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     sb7::gl! {
       gl::DeleteBuffers(2, &self.buf);
       gl::DeleteProgram(self.program);
@@ -42,7 +42,7 @@ impl App {
 }
 
 impl Application for App {
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     gl! {
       let mut texture = 0;
 
@@ -129,14 +129,14 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, _current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
     gl! {
       ClearBufferfv(COLOR, 0, [0.0f32, 0.25, 0.0, 1.0].as_ptr());
       DrawArrays(TRIANGLES, 0, 3);
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       DeleteProgram(self.prog);
       DeleteTextures(1, &self.texture);  
@@ -44,7 +44,7 @@ impl Application for App {
                 ..Default::default() }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     macro_rules! tex_data {
       (@a W) => ([ 0xFF, 0xFF, 0xFF, 0xFFu8 ]);
       (@a B) => ([ 0x00, 0x00, 0x00, 0x00u8 ]);
@@ -79,12 +79,12 @@ impl Application for App {
       TexParameteri(TEXTURE_2D, TEXTURE_MAG_FILTER, NEAREST as _);
     }
 
-    self.tex_object[1] = sb7::ktx::file::load("media/textures/pattern1.ktx").unwrap()
-                                                                            .0;
+    self.tex_object[1] = sb7::ktx::file::load(&context.media_path("media/textures/pattern1.ktx"))
+                           .unwrap().0;
 
-    self.object.load("media/objects/torus_nrms_tc.sbm");
+    self.object.load(&context.media_path("media/objects/torus_nrms_tc.sbm"));
 
-    self.load_shaders();
+    self.load_shaders(context);
 
     sb7::gl! {
       Enable(DEPTH_TEST);
@@ -92,10 +92,10 @@ impl Application for App {
     }
 
     let AppConfig { width, height, .. } = AppConfig::default();
-    self.on_resize(width as _, height as _);
+    self.on_resize(context, width as _, height as _);
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, current_time: f64) {
     let grey = [0.2, 0.2, 0.2, 1.0f32].as_ptr();
     let ones = [1.0f32].as_ptr();
 
@@ -116,14 +116,14 @@ impl Application for App {
     }
   }
 
-  fn on_resize(&mut self, w: i32, h: i32) {
+  fn on_resize(&mut self, _context: &mut AppContext, w: i32, h: i32) {
     let proj_matrix = sb7::vmath::perspective(60.0, w as f32 / h as f32, 0.1, 1000.0);
     sb7::gl! {
       UniformMatrix4fv(self.uniforms.proj_matrix, 1, FALSE, addr_of!(proj_matrix) as _);
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     sb7::gl! {
       DeleteTextures(2, self.tex_object.as_ptr());
       DeleteProgram(self.render_prog);
@@ -131,10 +131,10 @@ impl Application for App {
     }
   }
 
-  fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+  fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
     if let glfw::Action::Press = press {
       match key {
-        glfw::Key::R => self.load_shaders(),
+        glfw::Key::R => self.load_shaders(context),
         glfw::Key::T => {
           self.tex_index += 1;
           if self.tex_index > 1 {
@@ -151,14 +151,14 @@ fn main() {
   App::default().run();
 }
 impl App {
-  fn load_shaders(&mut self) {
+  fn load_shaders(&mut self, context: &mut AppContext) {
     if self.render_prog != 0 {
       sb7::gl! { DeleteProgram(self.render_prog) };
     }
 
     self.render_prog = sb7::program::link_from_shaders(&[
-      sb7::shader::load("media/shaders/simpletexcoords/render.vs.glsl", VERTEX_SHADER, true),
-      sb7::shader::load("media/shaders/simpletexcoords/render.fs.glsl", FRAGMENT_SHADER, true)
+      sb7::shader::load(&context.media_path("media/shaders/simpletexcoords/render.vs.glsl"), VERTEX_SHADER, true),
+      sb7::shader::load(&context.media_path("media/shaders/simpletexcoords/render.fs.glsl"), FRAGMENT_SHADER, true)
     ], true);
 
     let location = |name: &str| sb7::gl! {
@@ -173,6 +173,7 @@ impl App {
       UseProgram(self.render_prog);
     }
 
-    self.on_resize(self.info().width as _, self.info().height as _);
+    let (width, height) = (context.info.width, context.info.height);
+    self.on_resize(context, width as _, height as _);
   }
 }
@@ -43,7 +43,7 @@ struct App {
 }
 
 impl Application for App {
-  fn startup(&mut self) {
+  fn startup(&mut self, _context: &mut AppContext) {
     let vs_src = "
       #version 460 core
 
@@ -116,11 +116,11 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, context: &mut AppContext, current_time: f64) {
     gl! { ClearBufferfv(COLOR, 0, [0.0, 0.0, 0.0].as_ptr()); }
 
     let aspect = {
-      let AppConfig {width, height, ..} = self.info();
+      let AppConfig {width, height, ..} = context.info.clone();
       width as f32 / height as f32
     };
 
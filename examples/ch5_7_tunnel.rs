@@ -48,7 +48,7 @@ impl Application for App {
     }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     let vs_src = "
       #version 460 core
 
@@ -98,9 +98,9 @@ impl Application for App {
     self.uniforms.mvp    = get_location("mvp");
     self.uniforms.offset = get_location("offset");
 
-    self.tex_wall = sb7::ktx::file::load("media/textures/brick.ktx").unwrap().0;
-    self.tex_ceiling = sb7::ktx::file::load("media/textures/ceiling.ktx").unwrap().0;
-    self.tex_floor = sb7::ktx::file::load("media/textures/floor.ktx").unwrap().0;
+    self.tex_wall = sb7::ktx::file::load(&context.media_path("media/textures/brick.ktx")).unwrap().0;
+    self.tex_ceiling = sb7::ktx::file::load(&context.media_path("media/textures/ceiling.ktx")).unwrap().0;
+    self.tex_floor = sb7::ktx::file::load(&context.media_path("media/textures/floor.ktx")).unwrap().0;
   
     for tex in [self.tex_wall, self.tex_floor, self.tex_ceiling] {
       gl! {
@@ -116,7 +116,7 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, current_time: f64) {
+  fn render(&mut self, context: &mut AppContext, current_time: f64) {
     let t = current_time as f32;
     let black = [0.0, 0.0, 0.0, 0.0f32].as_ptr();
 
@@ -126,7 +126,7 @@ impl Application for App {
     }
 
     let proj_matrix = {
-      let AppConfig { width, height, .. } = self.info();
+      let AppConfig { width, height, .. } = context.info.clone();
       let aspect = width as f32 / height as f32;
       sb7::vmath::perspective(60.0, aspect, 0.1, 100.0)
     };
@@ -150,7 +150,7 @@ impl Application for App {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl!{
       gl::DeleteProgram(self.render_prog);
       gl::DeleteVertexArrays(1, &self.render_vao);
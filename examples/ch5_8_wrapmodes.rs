@@ -20,7 +20,7 @@
 // DEALINGS IN THE SOFTWARE.
 
 use gl::*;
-use sb7::{application::Application, gl};
+use sb7::{application::{Application, AppContext}, gl};
 
 #[derive(Default)]
 struct App {
@@ -38,7 +38,7 @@ impl Application for App {
     }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     let vs_src = "
       #version 460 core
 
@@ -73,7 +73,7 @@ impl Application for App {
     self.prog = sb7::program::link_from_shaders(&[vs, fs], true);
 
     let name = std::ffi::CString::new("offset").unwrap();
-    self.tex = sb7::ktx::file::load("media/textures/rightarrows.ktx").unwrap().0;
+    self.tex = sb7::ktx::file::load(&context.media_path("media/textures/rightarrows.ktx")).unwrap().0;
   
     gl! {
       BindTexture(TEXTURE_2D, self.tex);
@@ -85,7 +85,7 @@ impl Application for App {
     }
   }
 
-  fn render(&mut self, _: f64) {
+  fn render(&mut self, _context: &mut AppContext, _: f64) {
     let green = [0.0, 0.1, 0.0, 1.0f32].as_ptr();
     let yellow = [0.4, 0.4, 0.0, 1.0f32].as_ptr();
 
@@ -114,7 +114,7 @@ impl Application for App {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       DeleteShader(self.prog);
       DeleteVertexArrays(1, &self.vao);
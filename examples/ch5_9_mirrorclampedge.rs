@@ -42,12 +42,12 @@ struct App {
 }
 
 impl App {
-  fn load_shaders(&mut self) {
+  fn load_shaders(&mut self, context: &AppContext) {
     gl!(DeleteShader(self.render_prog));
 
     self.render_prog = program::link_from_shaders(&[
-      shader::load("media/shaders/mirrorclampedge/drawquad.vs.glsl", VERTEX_SHADER, true),
-      shader::load("media/shaders/mirrorclampedge/drawquad.fs.glsl", FRAGMENT_SHADER, true)
+      shader::load(&context.media_path("media/shaders/mirrorclampedge/drawquad.vs.glsl"), VERTEX_SHADER, true),
+      shader::load(&context.media_path("media/shaders/mirrorclampedge/drawquad.fs.glsl"), FRAGMENT_SHADER, true)
     ], true);
   }
 }
@@ -58,17 +58,17 @@ impl Application for App {
                 ..Default::default() }
   }
 
-  fn startup(&mut self) {
+  fn startup(&mut self, context: &mut AppContext) {
     // "media/textures/brick.ktx" has broken:
     // - https://github.com/openglsuperbible/sb7code/issues/44
-    self.tex = ktx::file::load("media/textures/brick.ktx").unwrap().0;
+    self.tex = ktx::file::load(&context.media_path("media/textures/brick.ktx")).unwrap().0;
 
-    self.load_shaders();
+    self.load_shaders(context);
 
     gl!(GenVertexArrays(1, &mut self.vao));
   }
 
-  fn render(&mut self, _current_time: f64) {
+  fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
     gl! {
       ClearBufferfv(COLOR, 0, color::Black.as_ptr());
 
@@ -92,7 +92,7 @@ impl Application for App {
     }
   }
 
-  fn shutdown(&mut self) {
+  fn shutdown(&mut self, _context: &mut AppContext) {
     gl! {
       DeleteVertexArrays(1, &self.vao);
       DeleteProgram(self.render_prog);
@@ -100,7 +100,7 @@ impl Application for App {
     }
   }
 
-  fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+  fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
     if let glfw::Action::Press = press {
       match key {
         glfw::Key::M => {
@@ -109,13 +109,13 @@ impl Application for App {
             DisplayMode::MirrorClampToEdge => DisplayMode::ClampToBorder,
           }
         }
-        glfw::Key::R => self.load_shaders(),
+        glfw::Key::R => self.load_shaders(context),
         _ => {}
       }
     }
   }
 
-  fn ui(&mut self, ui: &imgui::Ui) {
+  fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
     let win = imgui::Window::new("Press M to toggle wrap mode")
       .position([10.0, 10.0], imgui::Condition::Appearing);
     
@@ -20,32 +20,7 @@
 // DEALINGS IN THE SOFTWARE.
 
 use sb7::prelude::{AppConfig, Application};
-
-fn type_to_name(t: u32) -> &'static str {
-    match t {
-        gl::FLOAT => "float",
-        gl::FLOAT_VEC2 => "vec2",
-        gl::FLOAT_VEC3 => "vec3",
-        gl::FLOAT_VEC4 => "vec4",
-        gl::DOUBLE => "double",
-        gl::DOUBLE_VEC2 => "dvec2",
-        gl::DOUBLE_VEC3 => "dvec3",
-        gl::DOUBLE_VEC4 => "dvec4",
-        gl::INT => "int",
-        gl::INT_VEC2 => "ivec2",
-        gl::INT_VEC3 => "ivec3",
-        gl::INT_VEC4 => "ivec4",
-        gl::UNSIGNED_INT => "uint",
-        gl::UNSIGNED_INT_VEC2 => "uvec2",
-        gl::UNSIGNED_INT_VEC3 => "uvec3",
-        gl::UNSIGNED_INT_VEC4 => "uvec4",
-        gl::BOOL => "bool",
-        gl::BOOL_VEC2 => "bvec2",
-        gl::BOOL_VEC3 => "bvec3",
-        gl::BOOL_VEC4 => "bvec4",
-        _ => "unknown_type",
-    }
-}
+use sb7::reflect::reflect;
 
 #[inline(always)]
 fn parse_str(bytes: &[u8]) -> &str {
@@ -67,7 +42,7 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut sb7::application::AppContext) {
         let fs_src = "
             #version 460 core
             
@@ -112,18 +87,6 @@ impl Application for App {
             // gl::AttachShader(program, fs);
             // gl::LinkProgram(program);
 
-            let mut outputs = 0;
-
-            gl::GetProgramInterfaceiv(
-                program,
-                gl::PROGRAM_OUTPUT,
-                gl::ACTIVE_RESOURCES,
-                &mut outputs,
-            );
-
-            let props = [gl::TYPE, gl::LOCATION, gl::ARRAY_SIZE];
-            let mut params = [0; 4];
-            let mut name = [0u8; 64];
             let mut buffer = [0u8; 1024];
 
             gl::GetProgramInfoLog(program, 1, std::ptr::null_mut(), buffer.as_mut_ptr() as _);
@@ -131,49 +94,23 @@ impl Application for App {
             self.log += "Program Linked\n";
             self.log += parse_str(&buffer);
 
-            for i in 0..outputs {
-                gl::GetProgramResourceName(
-                    program,
-                    gl::PROGRAM_OUTPUT,
-                    i as _,
-                    1024,
-                    std::ptr::null_mut(),
-                    name.as_mut_ptr() as _,
-                );
-                gl::GetProgramResourceiv(
-                    program,
-                    gl::PROGRAM_OUTPUT,
-                    i as _,
-                    3,
-                    props.as_ptr(),
-                    3,
-                    std::ptr::null_mut(),
-                    params.as_mut_ptr(),
-                );
-                let type_name = type_to_name(params[0] as _);
-                if params[2] != 0 {
+            for (i, output) in reflect(program).outputs.iter().enumerate() {
+                if output.array_size != 0 {
                     self.log += &format!(
                         "Index {}: {} {}[{}] @ location {}.\n",
-                        i,
-                        type_name,
-                        parse_str(&name),
-                        params[2],
-                        params[1]
+                        i, output.kind, output.name, output.array_size, output.location
                     );
                 } else {
                     self.log += &format!(
                         "Index {}: {} {} @ location {}.\n",
-                        i,
-                        type_name,
-                        parse_str(&name),
-                        params[1]
+                        i, output.kind, output.name, output.location
                     );
                 }
             }
         }
     }
 
-    fn ui(&mut self, ui: &imgui_glfw_rs::imgui::Ui) {
+    fn ui(&mut self, _context: &mut sb7::application::AppContext, ui: &imgui_glfw_rs::imgui::Ui) {
         use imgui_glfw_rs::imgui;
         let win = imgui::Window::new("OpenGL SuperBible - Program Information")
             .no_decoration()
@@ -21,56 +21,29 @@
 
 use sb7::application::*;
 
-#[derive(Default)]
-struct Uniforms {
-    subroutine1: i32,
-}
-
 #[derive(Default)]
 struct App {
-    render_program: u32,
+    render_program: sb7::program::Program,
     vao: u32,
-    subroutines: [u32; 2],
-    uniforms: Uniforms,
 }
 
 impl App {
-    fn load_shaders(&mut self) {
-        if self.render_program != 0 {
-            unsafe { gl::DeleteProgram(self.render_program) };
-        }
-
-        self.render_program = sb7::program::link_from_shaders(
+    fn load_shaders(&mut self, context: &AppContext) {
+        self.render_program = sb7::program::Program::new(sb7::program::link_from_shaders(
             &[
                 sb7::shader::load(
-                    "media/shaders/subroutines/subroutines.vs.glsl",
+                    &context.media_path("media/shaders/subroutines/subroutines.vs.glsl"),
                     gl::VERTEX_SHADER,
                     true,
                 ),
                 sb7::shader::load(
-                    "media/shaders/subroutines/subroutines.fs.glsl",
+                    &context.media_path("media/shaders/subroutines/subroutines.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
             ],
             true,
-        );
-
-        unsafe {
-            let name = std::ffi::CString::new("myFunction1").unwrap();
-            self.subroutines[0] =
-                gl::GetSubroutineIndex(self.render_program, gl::FRAGMENT_SHADER, name.as_ptr());
-            let name = std::ffi::CString::new("myFunction2").unwrap();
-            self.subroutines[1] =
-                gl::GetSubroutineIndex(self.render_program, gl::FRAGMENT_SHADER, name.as_ptr());
-
-            let name = std::ffi::CString::new("mySubroutineUniform").unwrap();
-            self.uniforms.subroutine1 = gl::GetSubroutineUniformLocation(
-                self.render_program,
-                gl::FRAGMENT_SHADER,
-                name.as_ptr(),
-            );
-        }
+        ));
     }
 }
 
@@ -82,8 +55,8 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
@@ -91,21 +64,26 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, current_time: f64) {
         let i = current_time as usize;
         unsafe {
-            gl::UseProgram(self.render_program);
-
-            gl::UniformSubroutinesuiv(gl::FRAGMENT_SHADER, 1, &self.subroutines[i & 1]);
+            let function = ["myFunction1", "myFunction2"][i & 1];
+            self.render_program
+                .set_subroutine(sb7::shader::Stage::Fragment, "mySubroutineUniform", function);
+            self.render_program.use_program();
 
             gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
         }
     }
 
-    fn on_key(&mut self, key: imgui_glfw_rs::glfw::Key, press: imgui_glfw_rs::glfw::Action) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
+        self.render_program = Default::default();
+    }
+
+    fn on_key(&mut self, context: &mut AppContext, key: imgui_glfw_rs::glfw::Key, press: imgui_glfw_rs::glfw::Action) {
         if let imgui_glfw_rs::glfw::Action::Press = press {
             match key {
-                imgui_glfw_rs::glfw::Key::R => self.load_shaders(),
+                imgui_glfw_rs::glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
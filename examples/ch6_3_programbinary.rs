@@ -22,29 +22,18 @@
 use std::io::{Read, Write};
 use sb7::application::*;
 
-#[derive(Default)]
-struct Uniforms {
-    subroutine1: i32,
-}
-
 #[derive(Default)]
 struct App {
-    render_program: u32,
+    render_program: sb7::program::Program,
     vao: u32,
-    subroutines: [u32; 2],
-    uniforms: Uniforms,
 }
 
 impl App {
-    fn load_shaders(&mut self) {
-        if self.render_program != 0 {
-            unsafe { gl::DeleteProgram(self.render_program) };
-        }
-
+    fn load_shaders(&mut self, context: &AppContext) {
         unsafe {
-            self.render_program = gl::CreateProgram();
+            let program = gl::CreateProgram();
             gl::ProgramParameteri(
-                self.render_program,
+                program,
                 gl::PROGRAM_BINARY_RETRIEVABLE_HINT,
                 gl::TRUE as _,
             );
@@ -60,36 +49,36 @@ impl App {
 
                 let format = u32::from_le_bytes(format);
                 gl::ProgramBinary(
-                    self.render_program,
+                    program,
                     format,
                     data[..].as_ptr() as _,
                     data.len() as _,
                 );
 
                 // Don't need link program when load from binary
-                // gl::LinkProgram(self.render_program);
+                // gl::LinkProgram(program);
             } else {
                 // compile form source
                 let vs = sb7::shader::load(
-                    "media/shaders/subroutines/subroutines.vs.glsl",
+                    &context.media_path("media/shaders/subroutines/subroutines.vs.glsl"),
                     gl::VERTEX_SHADER,
                     true,
                 );
                 let fs = sb7::shader::load(
-                    "media/shaders/subroutines/subroutines.fs.glsl",
+                    &context.media_path("media/shaders/subroutines/subroutines.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 );
 
-                gl::AttachShader(self.render_program, vs);
-                gl::AttachShader(self.render_program, fs);
-                gl::LinkProgram(self.render_program);
+                gl::AttachShader(program, vs);
+                gl::AttachShader(program, fs);
+                gl::LinkProgram(program);
                 gl::DeleteShader(vs);
                 gl::DeleteShader(fs);
 
                 // query binary size and format
                 let mut size = 0;
-                gl::GetProgramiv(self.render_program, gl::PROGRAM_BINARY_LENGTH, &mut size);
+                gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut size);
 
                 // alloc buffer to storage binary
                 let mut buf: Vec<u8> = Vec::with_capacity(size as _);
@@ -98,7 +87,7 @@ impl App {
                 // get binary data from program
                 let mut format = 0;
                 gl::GetProgramBinary(
-                    self.render_program,
+                    program,
                     size,
                     std::ptr::null_mut(),
                     &mut format,
@@ -111,22 +100,8 @@ impl App {
                 file.write_all(&u32::to_le_bytes(format)).unwrap();
                 file.write_all(&buf).unwrap();
             }
-        }
 
-        unsafe {
-            let name = std::ffi::CString::new("myFunction1").unwrap();
-            self.subroutines[0] =
-                gl::GetSubroutineIndex(self.render_program, gl::FRAGMENT_SHADER, name.as_ptr());
-            let name = std::ffi::CString::new("myFunction2").unwrap();
-            self.subroutines[1] =
-                gl::GetSubroutineIndex(self.render_program, gl::FRAGMENT_SHADER, name.as_ptr());
-
-            let name = std::ffi::CString::new("mySubroutineUniform").unwrap();
-            self.uniforms.subroutine1 = gl::GetSubroutineUniformLocation(
-                self.render_program,
-                gl::FRAGMENT_SHADER,
-                name.as_ptr(),
-            );
+            self.render_program = sb7::program::Program::new(program);
         }
     }
 }
@@ -139,8 +114,8 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
@@ -148,21 +123,26 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, current_time: f64) {
         let i = current_time as usize;
         unsafe {
-            gl::UseProgram(self.render_program);
-
-            gl::UniformSubroutinesuiv(gl::FRAGMENT_SHADER, 1, &self.subroutines[i & 1]);
+            let function = ["myFunction1", "myFunction2"][i & 1];
+            self.render_program
+                .set_subroutine(sb7::shader::Stage::Fragment, "mySubroutineUniform", function);
+            self.render_program.use_program();
 
             gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
         }
     }
 
-    fn on_key(&mut self, key: imgui_glfw_rs::glfw::Key, press: imgui_glfw_rs::glfw::Action) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
+        self.render_program = Default::default();
+    }
+
+    fn on_key(&mut self, context: &mut AppContext, key: imgui_glfw_rs::glfw::Key, press: imgui_glfw_rs::glfw::Action) {
         if let imgui_glfw_rs::glfw::Action::Press = press {
             match key {
-                imgui_glfw_rs::glfw::Key::R => self.load_shaders(),
+                imgui_glfw_rs::glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
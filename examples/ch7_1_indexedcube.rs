@@ -37,7 +37,7 @@ struct App {
 }
 
 impl Application for App {
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let vs_src = "
             #version 460 core
             
@@ -147,7 +147,7 @@ impl Application for App {
             self.proj_location = gl::GetUniformLocation(self.prog, c_str.as_ptr());
 
             let AppConfig { width, height, .. } = AppConfig::default();
-            self.on_resize(width as _, height as _);
+            self.on_resize(context, width as _, height as _);
 
             gl::Enable(gl::DEPTH_TEST);
             // gl::Enable(gl::CULL_FACE);
@@ -155,7 +155,7 @@ impl Application for App {
         }
     }
 
-    fn on_resize(&mut self, w: i32, h: i32) {
+    fn on_resize(&mut self, _context: &mut AppContext, w: i32, h: i32) {
         let aspect = w as f32 / h as f32;
         self.proj_matrix = perspective(20.0, aspect, 0.1, 1000.0);
 
@@ -170,7 +170,7 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, current_time: f64) {
         let green = [0.0, 0.25, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
 
@@ -212,7 +212,7 @@ impl Application for App {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteBuffers(1, &self.index_buf);
@@ -51,7 +51,7 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let grass_vs_src = r#"
             // Vertex Shader
             // Graham Sellers
@@ -200,7 +200,7 @@ impl Application for App {
             let c_str = std::ffi::CString::new("mvpMatrix").unwrap();
             self.uniforms.mvp_matrix = gl::GetUniformLocation(self.grass_program, c_str.as_ptr());
 
-            let load = |path| ktx::file::load(path).unwrap().0;
+            let load = |path| ktx::file::load(&context.media_path(path)).unwrap().0;
             gl::ActiveTexture(gl::TEXTURE1);
             self.tex_grass_length = load("media/textures/grass_length.ktx");
             gl::ActiveTexture(gl::TEXTURE2);
@@ -215,7 +215,7 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         unsafe {
             let t = (current_time * 0.02) as f32;
             let r = 550f32;
@@ -230,7 +230,7 @@ impl Application for App {
                 vec3!(0.0, -50., 0.),
                 vec3!(0.0, 1.0, 0.0),
             );
-            let AppConfig { width, height, .. } = self.info();
+            let AppConfig { width, height, .. } = context.info.clone();
             let prj_matrix = perspective(45., width as f32 / height as f32, 0.01, 1000.);
             let mvp_matrix = prj_matrix * mv_matrix;
 
@@ -247,7 +247,7 @@ impl Application for App {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.grass_program);
             gl::DeleteVertexArrays(1, &self.grass_vao);
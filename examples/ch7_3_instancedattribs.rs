@@ -38,7 +38,7 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let square_vs_source = r#"
             #version 410 core
 
@@ -182,7 +182,7 @@ impl Application for App {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.square_vao);
             gl::DeleteBuffers(1, &self.square_buffer);
@@ -190,7 +190,7 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         unsafe {
             let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
 
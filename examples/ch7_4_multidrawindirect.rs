@@ -78,16 +78,16 @@ impl Mode {
 }
 
 impl App {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         self.render_program = program::link_from_shaders(
             &[
                 shader::load(
-                    "media/shaders/multidrawindirect/render.vs.glsl",
+                    &context.media_path("media/shaders/multidrawindirect/render.vs.glsl"),
                     gl::VERTEX_SHADER,
                     true,
                 ),
                 shader::load(
-                    "media/shaders/multidrawindirect/render.fs.glsl",
+                    &context.media_path("media/shaders/multidrawindirect/render.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
@@ -117,10 +117,10 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
-        self.object.load("media/objects/asteroids.sbm");
+        self.object.load(&context.media_path("media/objects/asteroids.sbm"));
 
         unsafe {
             gl::BindVertexArray(self.object.get_vao());
@@ -187,7 +187,7 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let one = 1.0f32;
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
 
@@ -215,7 +215,7 @@ impl Application for App {
                 vec3!(0., 0., 260.),
                 vec3!(0.1 - (t * 0.1).cos() * 0.3, 1.0, 0.0).normalize(),
             );
-            let AppConfig { width, height, .. } = self.info();
+            let AppConfig { width, height, .. } = context.info.clone();
             let proj_matrix = perspective(50., width as f32 / height as f32, 0.1, 2000.0);
             let viewproj_matrix = proj_matrix * view_matrix;
 
@@ -266,7 +266,7 @@ impl Application for App {
     }
 
     #[rustfmt::skip]
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Settings")
             .position([10., 10.], imgui::Condition::Appearing)
             .begin(ui)
@@ -292,7 +292,7 @@ impl Application for App {
 
             toggle_btn(2, &ui,&mut || {
                 self.vsync = !self.vsync;
-                self.set_vsync(self.vsync);
+                context.set_vsync(self.vsync);
             });
             ui.same_line(); ui.text(format!("Vsync: {:?}", self.vsync));
 
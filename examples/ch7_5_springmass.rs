@@ -56,45 +56,34 @@ impl SpringMassApp {
         }
     }
 
-    fn load_shaders(&mut self) {
-        let vs = shader::load(
-            "media/shaders/springmass/update.vs.glsl",
-            gl::VERTEX_SHADER,
-            true,
-        );
+    fn load_shaders(&mut self, context: &AppContext) {
+        // Keep the current programs if the new ones don't build, so a typo
+        // while editing the shaders doesn't end the app
+        let update_program = match program::Builder::new()
+            .file(&context.media_path("media/shaders/springmass/update.vs.glsl"), shader::Stage::Vertex)
+            .transform_feedback(&["tf_position_mass", "tf_velocity"], program::FeedbackMode::Separate)
+            .build()
+        {
+            Ok(program) => program,
+            Err(err) => {
+                eprintln!("{}", err);
+                return;
+            }
+        };
 
         unsafe {
             if self.m_update_program != 0 {
                 gl::DeleteProgram(self.m_update_program);
             }
-
-            self.m_update_program = gl::CreateProgram();
-            gl::AttachShader(self.m_update_program, vs);
-
-            let tf_varyings = [
-                std::ffi::CString::new("tf_position_mass").unwrap(),
-                std::ffi::CString::new("tf_velocity").unwrap(),
-            ];
-            let tf_varyings: Vec<_> = tf_varyings.iter().map(|s| s.as_ptr()).collect();
-
-            gl::TransformFeedbackVaryings(
-                self.m_update_program,
-                2,
-                tf_varyings.as_ptr(),
-                gl::SEPARATE_ATTRIBS,
-            );
-
-            gl::LinkProgram(self.m_update_program);
-
-            gl::DeleteShader(vs);
+            self.m_update_program = update_program;
 
             let vs = shader::load(
-                "media/shaders/springmass/render.vs.glsl",
+                &context.media_path("media/shaders/springmass/render.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/springmass/render.fs.glsl",
+                &context.media_path("media/shaders/springmass/render.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -121,8 +110,8 @@ impl Application for SpringMassApp {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             let mut initial_positions = [Vec4::default(); POINTS_TOTAL];
@@ -256,7 +245,7 @@ impl Application for SpringMassApp {
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         unsafe {
             gl::UseProgram(self.m_update_program);
 
@@ -309,7 +298,7 @@ impl Application for SpringMassApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.m_update_program);
             gl::DeleteBuffers(5, self.m_vbo.as_ptr());
@@ -317,7 +306,7 @@ impl Application for SpringMassApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Settings")
             .position([10.0, 10.0], imgui::Condition::Once)
             .begin(ui)
@@ -38,49 +38,34 @@ struct ClipDistanceApp {
 }
 
 impl ClipDistanceApp {
-    fn load_shaders(&mut self) {
-        unsafe {
-            if self.render_program != 0 {
-                gl::DeleteProgram(self.render_program);
-                self.render_program = 0;
-            }
-
-            self.render_program = program::link_from_shaders(
-                &[
-                    shader::load(
-                        "media/shaders/clipdistance/render.vs.glsl",
-                        gl::VERTEX_SHADER,
-                        true,
-                    ),
-                    shader::load(
-                        "media/shaders/clipdistance/render.fs.glsl",
-                        gl::FRAGMENT_SHADER,
-                        true,
-                    ),
-                ],
-                true,
-            );
-
-            let get_loc = |name: &str| {
-                let name = std::ffi::CString::new(name).unwrap();
-                gl::GetUniformLocation(self.render_program, name.as_ptr())
-            };
-
-            self.uniforms.proj_matrix = get_loc("proj_matrix");
-            self.uniforms.mv_matrix = get_loc("mv_matrix");
-            self.uniforms.clip_plane = get_loc("clip_plane");
-            self.uniforms.clip_sphere = get_loc("clip_sphere");
-        }
+    fn get_uniforms(&mut self) {
+        let get_loc = |name: &str| {
+            let name = std::ffi::CString::new(name).unwrap();
+            unsafe { gl::GetUniformLocation(self.render_program, name.as_ptr()) }
+        };
+
+        self.uniforms.proj_matrix = get_loc("proj_matrix");
+        self.uniforms.mv_matrix = get_loc("mv_matrix");
+        self.uniforms.clip_plane = get_loc("clip_plane");
+        self.uniforms.clip_sphere = get_loc("clip_sphere");
     }
 }
 
 impl Application for ClipDistanceApp {
-    fn startup(&mut self) {
-        self.load_shaders();
-        self.object.load("media/objects/dragon.sbm");
+    fn try_startup(&mut self, context: &mut AppContext) -> Result<(), Box<dyn std::error::Error>> {
+        // Edit the shaders while the app runs to see the changes
+        let builder = context
+            .builder()
+            .file("media/shaders/clipdistance/render.vs.glsl", shader::Stage::Vertex)
+            .file("media/shaders/clipdistance/render.fs.glsl", shader::Stage::Fragment);
+        self.render_program = context.load_watched(builder)?;
+        self.get_uniforms();
+
+        self.object.load(&context.media_path("media/objects/dragon.sbm"));
+        Ok(())
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
 
@@ -103,7 +88,7 @@ impl Application for ClipDistanceApp {
             let proj_matrix = perspective(
                 50.0f32,
                 {
-                    let AppConfig { width, height, .. } = self.info();
+                    let AppConfig { width, height, .. } = context.info.clone();
                     width as f32 / height as f32
                 },
                 0.1f32,
@@ -155,21 +140,26 @@ impl Application for ClipDistanceApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         self.object.free();
         unsafe {
             gl::DeleteProgram(self.render_program);
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
-        if let glfw::Action::Press = press {
-            match key {
-                glfw::Key::R => self.load_shaders(),
-                glfw::Key::P => self.paused = !self.paused,
-                _ => {}
-            }
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
+        if let (glfw::Key::P, glfw::Action::Press) = (key, press) {
+            self.paused = !self.paused;
+        }
+    }
+
+    fn on_program_reload(&mut self, _context: &mut AppContext, old: u32, new: u32) -> bool {
+        if old != self.render_program {
+            return false;
         }
+        self.render_program = new;
+        self.get_uniforms();
+        true
     }
 }
 
@@ -38,7 +38,7 @@ impl Application for MultiViewportApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -204,7 +204,7 @@ impl Application for MultiViewportApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
 
@@ -213,7 +213,7 @@ impl Application for MultiViewportApp {
             gl::ClearBufferfv(gl::DEPTH, 0, &one);
 
             // Each rectangle will be 7/16 of the screen
-            let AppConfig { width, height, .. } = self.info();
+            let AppConfig { width, height, .. } = context.info.clone();
             let viewport_width = 7.0 * width as f32 / 16.0;
             let viewport_height = 7.0 * height as f32 / 16.0;
 
@@ -275,7 +275,7 @@ impl Application for MultiViewportApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteProgram(self.program);
@@ -36,7 +36,7 @@ impl Application for TessModesApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = &cstring(
@@ -197,7 +197,7 @@ impl Application for TessModesApp {
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         unsafe {
             gl::ClearBufferfv(gl::COLOR, 0, color::Black.as_ptr());
 
@@ -207,7 +207,7 @@ impl Application for TessModesApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
 
@@ -217,7 +217,7 @@ impl Application for TessModesApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
                 glfw::Key::M => self.program_index = (self.program_index + 1) % 3,
@@ -226,7 +226,7 @@ impl Application for TessModesApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         static NAMES: [&str; 3] = ["EQUALS", "FRANCT_EVEN", "FRANCT_ODD"];
         if let Some(win) = imgui::Window::new("ui")
             .position([10.0, 10.0], imgui::Condition::Once)
@@ -53,40 +53,20 @@ impl DismapApp {
         }
     }
 
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         unsafe {
+            let program = match program::load_dir(&context.media_path("media/shaders/dispmap"), "dispmap") {
+                Ok((program, _)) => program,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    return;
+                }
+            };
+
             if self.program != 0 {
                 gl::DeleteProgram(self.program);
             }
-
-            let vs = shader::load(
-                "media/shaders/dispmap/dispmap.vs.glsl",
-                gl::VERTEX_SHADER,
-                true,
-            );
-            let tcs = shader::load(
-                "media/shaders/dispmap/dispmap.tcs.glsl",
-                gl::TESS_CONTROL_SHADER,
-                true,
-            );
-            let tes = shader::load(
-                "media/shaders/dispmap/dispmap.tes.glsl",
-                gl::TESS_EVALUATION_SHADER,
-                true,
-            );
-            let fs = shader::load(
-                "media/shaders/dispmap/dispmap.fs.glsl",
-                gl::FRAGMENT_SHADER,
-                true,
-            );
-
-            self.program = gl::CreateProgram();
-            gl::AttachShader(self.program, vs);
-            gl::AttachShader(self.program, tcs);
-            gl::AttachShader(self.program, tes);
-            gl::AttachShader(self.program, fs);
-
-            gl::LinkProgram(self.program);
+            self.program = program;
 
             let get_loc = |name| {
                 let name = std::ffi::CString::new(name).unwrap();
@@ -111,8 +91,8 @@ impl Application for DismapApp {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
@@ -122,15 +102,15 @@ impl Application for DismapApp {
 
             gl::Enable(gl::CULL_FACE);
 
-            self.tex_displacement = ktx::file::load("media/textures/terragen1.ktx").unwrap().0;
+            self.tex_displacement = ktx::file::load(&context.media_path("media/textures/terragen1.ktx")).unwrap().0;
             gl::ActiveTexture(gl::TEXTURE1);
-            self.tex_color = ktx::file::load("media/textures/terragen_color.ktx")
+            self.tex_color = ktx::file::load(&context.media_path("media/textures/terragen_color.ktx"))
                 .unwrap()
                 .0;
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.85, 0.95, 1.0, 1.0f32].as_ptr();
         let one = 1.0f32;
         unsafe {
@@ -157,7 +137,7 @@ impl Application for DismapApp {
             let proj_matrix = perspective(
                 60.0,
                 {
-                    let AppConfig { width, height, .. } = self.info();
+                    let AppConfig { width, height, .. } = context.info.clone();
                     width as f32 / height as f32
                 },
                 0.1,
@@ -212,14 +192,14 @@ impl Application for DismapApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteProgram(self.program);
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Repeat = press {
             match key {
                 glfw::Key::KpAdd => self.dmap_depth += 0.1,
@@ -235,13 +215,13 @@ impl Application for DismapApp {
                 glfw::Key::D => self.enable_displacement = !self.enable_displacement,
                 glfw::Key::W => self.wireframe = !self.wireframe,
                 glfw::Key::P => self.paused = !self.paused,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Help")
             .position([10.0, 10.0], imgui::Condition::Once)
             .begin(ui)
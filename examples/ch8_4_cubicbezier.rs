@@ -65,8 +65,8 @@ impl Application for CubicbezierApp {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             gl::GenVertexArrays(1, &mut self.patch_vao);
@@ -108,7 +108,7 @@ impl Application for CubicbezierApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         unsafe {
             let gray = [0.1f32, 0.1, 0.1, 0.1];
             let one = 1.0f32;
@@ -177,7 +177,7 @@ impl Application for CubicbezierApp {
             let proj_matrix = perspective(
                 50.0f32,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 1.0f32,
@@ -250,20 +250,20 @@ impl Application for CubicbezierApp {
         }
     }
 
-    fn on_key(&mut self, _key: glfw::Key, _press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, _key: glfw::Key, _press: glfw::Action) {
         if let glfw::Action::Press = _press {
             match _key {
                 glfw::Key::C => self.show_cage = !self.show_cage,
                 glfw::Key::X => self.show_points = !self.show_points,
                 glfw::Key::W => self.wireframe = !self.wireframe,
                 glfw::Key::P => self.paused = !self.paused,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.tess_program);
             gl::DeleteProgram(self.draw_cp_program);
@@ -273,7 +273,7 @@ impl Application for CubicbezierApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Help")
             .position([2.0, 2.0], imgui::Condition::Once)
             .begin(ui)
@@ -297,7 +297,7 @@ impl Application for CubicbezierApp {
 }
 
 impl CubicbezierApp {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         unsafe {
             if self.tess_program != 0 {
                 gl::DeleteProgram(self.tess_program);
@@ -306,22 +306,22 @@ impl CubicbezierApp {
             self.tess_program = program::link_from_shaders(
                 &[
                     shader::load(
-                        "media/shaders/cubicbezier/cubicbezier.vs.glsl",
+                        &context.media_path("media/shaders/cubicbezier/cubicbezier.vs.glsl"),
                         gl::VERTEX_SHADER,
                         true,
                     ),
                     shader::load(
-                        "media/shaders/cubicbezier/cubicbezier.tcs.glsl",
+                        &context.media_path("media/shaders/cubicbezier/cubicbezier.tcs.glsl"),
                         gl::TESS_CONTROL_SHADER,
                         true,
                     ),
                     shader::load(
-                        "media/shaders/cubicbezier/cubicbezier.tes.glsl",
+                        &context.media_path("media/shaders/cubicbezier/cubicbezier.tes.glsl"),
                         gl::TESS_EVALUATION_SHADER,
                         true,
                     ),
                     shader::load(
-                        "media/shaders/cubicbezier/cubicbezier.fs.glsl",
+                        &context.media_path("media/shaders/cubicbezier/cubicbezier.fs.glsl"),
                         gl::FRAGMENT_SHADER,
                         true,
                     ),
@@ -345,12 +345,12 @@ impl CubicbezierApp {
             self.draw_cp_program = program::link_from_shaders(
                 &[
                     shader::load(
-                        "media/shaders/cubicbezier/draw-control-points.vs.glsl",
+                        &context.media_path("media/shaders/cubicbezier/draw-control-points.vs.glsl"),
                         gl::VERTEX_SHADER,
                         true,
                     ),
                     shader::load(
-                        "media/shaders/cubicbezier/draw-control-points.fs.glsl",
+                        &context.media_path("media/shaders/cubicbezier/draw-control-points.fs.glsl"),
                         gl::FRAGMENT_SHADER,
                         true,
                     ),
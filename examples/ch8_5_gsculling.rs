@@ -38,7 +38,7 @@ impl Application for GscullingApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -169,7 +169,7 @@ impl Application for GscullingApp {
             self.mvp_location = get_loc("mvpMatrix");
             self.viewpoint_location = get_loc("viewpoint");
 
-            self.object.load("media/objects/dragon.sbm");
+            self.object.load(&context.media_path("media/objects/dragon.sbm"));
 
             gl::Disable(gl::CULL_FACE);
             gl::Enable(gl::DEPTH_TEST);
@@ -177,7 +177,7 @@ impl Application for GscullingApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 0.0f32];
         let one = 1.0f32;
         let f = current_time as f32;
@@ -191,7 +191,7 @@ impl Application for GscullingApp {
             let proj_matrix = perspective(
                 50.0f32,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1f32,
@@ -229,7 +229,7 @@ impl Application for GscullingApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         self.object.free();
         unsafe { gl::DeleteProgram(self.program) };
     }
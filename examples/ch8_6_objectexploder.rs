@@ -39,7 +39,7 @@ impl Application for App {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -155,14 +155,14 @@ impl Application for App {
             self.proj_location = get_loc("proj_matrix");
             self.explode_factor_location = get_loc("explode_factor");
 
-            self.object.load("media/objects/torus.sbm");
+            self.object.load(&context.media_path("media/objects/torus.sbm"));
 
             gl::Enable(gl::DEPTH_TEST);
             gl::DepthFunc(gl::LEQUAL);
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 0.0f32];
         let one = 1.0f32;
         let f = current_time as f32;
@@ -176,7 +176,7 @@ impl Application for App {
             let proj_matrix = perspective(
                 50.0f32,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1f32,
@@ -210,7 +210,7 @@ impl Application for App {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         self.object.free();
         unsafe { gl::DeleteProgram(self.program) };
     }
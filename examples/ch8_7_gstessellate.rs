@@ -39,7 +39,7 @@ impl Application for GsTessellateApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -226,7 +226,7 @@ impl Application for GsTessellateApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 0.0f32];
         let one = 1.0f32;
         let f = current_time as f32;
@@ -240,7 +240,7 @@ impl Application for GsTessellateApp {
             let proj_matrix = perspective(
                 50.0f32,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1f32,
@@ -274,7 +274,7 @@ impl Application for GsTessellateApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program);
             gl::DeleteVertexArrays(1, &self.vao);
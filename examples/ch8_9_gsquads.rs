@@ -54,16 +54,16 @@ impl Application for GsQuadsApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
             gl::BindVertexArray(self.vao);
 
-            self.load_shaders();
+            self.load_shaders(context);
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program_fans);
             gl::DeleteProgram(self.program_linesadjacency);
@@ -71,7 +71,7 @@ impl Application for GsQuadsApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         unsafe {
             let black = [0.0, 0.25, 0.0, 1.0f32].as_ptr();
 
@@ -94,7 +94,7 @@ impl Application for GsQuadsApp {
             let proj_matrix = perspective(
                 50.0,
                 {
-                    let i = self.info();
+                    let i = context.info.clone();
                     i.width as f32 / i.height as f32
                 },
                 0.1,
@@ -131,7 +131,7 @@ impl Application for GsQuadsApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
                 glfw::Key::Kp1 => self.mode = 0,
@@ -139,14 +139,14 @@ impl Application for GsQuadsApp {
                 glfw::Key::KpAdd => self.vid_offset += 1,
                 glfw::Key::KpSubtract => self.vid_offset -= 1,
                 glfw::Key::P => self.paused = !self.paused,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 glfw::Key::M => self.mode = (self.mode + 1) % 2,
                 _ => {}
             }
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         match self.mode {
             0 => ui.text("Drawing quads using GL_TRIANGLE_FAN"),
             1 => ui.text("Drawing quads using geometry shaders and GL_LINES_ADJACENCY"),
@@ -160,7 +160,7 @@ impl Application for GsQuadsApp {
 }
 
 impl GsQuadsApp {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         unsafe {
             unsafe fn get_uniform_loc(prog: GLuint, name: &str) -> GLint {
                 let name = std::ffi::CString::new(name).unwrap();
@@ -174,12 +174,12 @@ impl GsQuadsApp {
             self.program_fans = gl::CreateProgram();
 
             let vs = shader::load(
-                "media/shaders/gsquads/quadsasfans.vs.glsl",
+                &context.media_path("media/shaders/gsquads/quadsasfans.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/gsquads/quadsasfans.fs.glsl",
+                &context.media_path("media/shaders/gsquads/quadsasfans.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -202,17 +202,17 @@ impl GsQuadsApp {
             self.program_linesadjacency = gl::CreateProgram();
 
             let vs = shader::load(
-                "media/shaders/gsquads/quadsaslinesadj.vs.glsl",
+                &context.media_path("media/shaders/gsquads/quadsaslinesadj.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let gs = shader::load(
-                "media/shaders/gsquads/quadsaslinesadj.gs.glsl",
+                &context.media_path("media/shaders/gsquads/quadsaslinesadj.gs.glsl"),
                 gl::GEOMETRY_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/gsquads/quadsaslinesadj.fs.glsl",
+                &context.media_path("media/shaders/gsquads/quadsaslinesadj.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -42,7 +42,7 @@ impl Application for MSAANativeApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -152,9 +152,9 @@ impl Application for MSAANativeApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
-        let info = self.info();
+        let info = context.info.clone();
         let current_time = current_time as f32;
 
         unsafe {
@@ -214,7 +214,7 @@ impl Application for MSAANativeApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteBuffers(1, &self.index_buffer);
             gl::DeleteBuffers(1, &self.position_buffer);
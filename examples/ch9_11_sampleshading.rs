@@ -43,7 +43,7 @@ impl Application for SamplesShadingApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -165,9 +165,9 @@ impl Application for SamplesShadingApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
-        let info = self.info();
+        let info = context.info.clone();
         let current_time = current_time as f32;
 
         unsafe {
@@ -233,7 +233,7 @@ impl Application for SamplesShadingApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteBuffers(1, &self.index_buffer);
             gl::DeleteBuffers(1, &self.position_buffer);
@@ -241,7 +241,7 @@ impl Application for SamplesShadingApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
                 glfw::Key::S => self.sampleshading = !self.sampleshading,
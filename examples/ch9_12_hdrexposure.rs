@@ -60,7 +60,7 @@ impl Application for HDRExposureApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_source = cstring(
@@ -101,7 +101,7 @@ impl Application for HDRExposureApp {
             gl::GenTextures(1, &mut self.texture);
 
             // Load texture from file
-            ktx::file::load_with_tex("media/textures/treelights_2k.ktx", self.texture).unwrap();
+            ktx::file::load_with_tex(&context.media_path("media/textures/treelights_2k.ktx"), self.texture).unwrap();
 
             // Now bind it to the context using the GL_TEXTURE_2D binding point
             gl::BindTexture(gl::TEXTURE_2D, self.texture);
@@ -131,7 +131,7 @@ impl Application for HDRExposureApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program);
             gl::DeleteTextures(1, &self.texture);
@@ -139,7 +139,7 @@ impl Application for HDRExposureApp {
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         let green = [0.0, 0.25, 0.0, 1.0].as_ptr();
         unsafe {
             gl::ClearBufferfv(gl::COLOR, 0, green);
@@ -152,7 +152,7 @@ impl Application for HDRExposureApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Debug")
             .position([10.0, 10.0], imgui::Condition::Once)
             .begin(ui)
@@ -165,7 +165,7 @@ impl Application for HDRExposureApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
                 glfw::Key::KpAdd => self.exposure += 0.1,
@@ -40,25 +40,16 @@ struct HDRTonemapApp {
     tex_src: GLuint,
     // tex_lut: GLuint,
 
-    program_naive: GLuint,
-    program_exposure: GLuint,
-    program_adaptive: GLuint,
+    // The naive, exposure and adaptive programs, all built from
+    // tonemap.fs.glsl
+    tonemap: program::Variants,
     vao: GLuint,
     exposure: f32,
-    mode: i32,
-
-    uniforms: Uniforms,
+    mode: usize,
 }
 
-#[derive(Default)]
-struct Uniforms {
-    exposure: ExposureUniforms,
-}
-
-#[derive(Default)]
-struct ExposureUniforms {
-    exposure: GLint,
-}
+// Defines of the tonemap.fs.glsl variant of each mode
+const MODES: [&[(&str, &str)]; 3] = [&[], &[("EXPOSURE", "")], &[("ADAPTIVE", "")]];
 
 impl HDRTonemapApp {
     fn new() -> Self {
@@ -69,73 +60,12 @@ impl HDRTonemapApp {
     }
 
     fn load_shaders(&mut self) {
-        unsafe {
-            if self.program_naive != 0 {
-                gl::DeleteProgram(self.program_naive);
-            }
-
-            self.program_naive = gl::CreateProgram();
-
-            let vs = shader::load(
-                "media/shaders/hdrtonemap/tonemap.vs.glsl",
-                gl::VERTEX_SHADER,
-                true,
-            );
-            let fs = shader::load(
-                "media/shaders/hdrtonemap/tonemap_naive.fs.glsl",
-                gl::FRAGMENT_SHADER,
-                true,
-            );
-
-            gl::AttachShader(self.program_naive, vs);
-            gl::AttachShader(self.program_naive, fs);
-
-            gl::LinkProgram(self.program_naive);
-
-            gl::DeleteShader(fs);
-
-            if self.program_adaptive != 0 {
-                gl::DeleteProgram(self.program_adaptive);
+        // Rebuild every mode now, so broken shaders are reported right away
+        self.tonemap.clear();
+        for defines in MODES {
+            if let Err(err) = self.tonemap.get(defines) {
+                eprintln!("{}", err);
             }
-
-            self.program_adaptive = gl::CreateProgram();
-
-            let fs = shader::load(
-                "media/shaders/hdrtonemap/tonemap_adaptive.fs.glsl",
-                gl::FRAGMENT_SHADER,
-                true,
-            );
-
-            gl::AttachShader(self.program_adaptive, vs);
-            gl::AttachShader(self.program_adaptive, fs);
-
-            gl::LinkProgram(self.program_adaptive);
-
-            gl::DeleteShader(fs);
-
-            if self.program_exposure != 0 {
-                gl::DeleteProgram(self.program_exposure);
-            }
-
-            self.program_exposure = gl::CreateProgram();
-
-            let fs = shader::load(
-                "media/shaders/hdrtonemap/tonemap_exposure.fs.glsl",
-                gl::FRAGMENT_SHADER,
-                true,
-            );
-
-            gl::AttachShader(self.program_exposure, vs);
-            gl::AttachShader(self.program_exposure, fs);
-
-            gl::LinkProgram(self.program_exposure);
-
-            let name = std::ffi::CString::new("exposure").unwrap();
-            self.uniforms.exposure.exposure =
-                gl::GetUniformLocation(self.program_exposure, name.as_ptr());
-
-            gl::DeleteShader(fs);
-            gl::DeleteShader(vs);
         }
     }
 }
@@ -148,10 +78,10 @@ impl Application for HDRTonemapApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             // Load texture from file
-            self.tex_src = ktx::file::load("media/textures/treelights_2k.ktx")
+            self.tex_src = ktx::file::load(&context.media_path("media/textures/treelights_2k.ktx"))
                 .unwrap()
                 .0;
 
@@ -161,6 +91,11 @@ impl Application for HDRTonemapApp {
             gl::CreateVertexArrays(1, &mut self.vao);
             gl::BindVertexArray(self.vao);
 
+            self.tonemap = program::Variants::new(&[
+                ("media/shaders/hdrtonemap/tonemap.vs.glsl", shader::Stage::Vertex),
+                ("media/shaders/hdrtonemap/tonemap.fs.glsl", shader::Stage::Fragment),
+            ])
+            .assets(&context.assets());
             self.load_shaders();
 
             /* let exposure_lut = [
@@ -191,18 +126,16 @@ impl Application for HDRTonemapApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
-            gl::DeleteProgram(self.program_adaptive);
-            gl::DeleteProgram(self.program_exposure);
-            gl::DeleteProgram(self.program_naive);
+            self.tonemap.clear();
             // gl::DeleteTextures(1, &self.tex_lut);
             gl::DeleteTextures(1, &self.tex_src);
             gl::DeleteVertexArrays(1, &self.vao);
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         let green = [0.0, 0.25, 0.0, 1.0].as_ptr();
         unsafe {
             gl::ClearBufferfv(gl::COLOR, 0, green);
@@ -212,20 +145,17 @@ impl Application for HDRTonemapApp {
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.tex_src);
 
-            match self.mode {
-                0 => gl::UseProgram(self.program_naive),
-                1 => {
-                    gl::UseProgram(self.program_exposure);
-                    gl::Uniform1f(self.uniforms.exposure.exposure, self.exposure);
-                }
-                2 => gl::UseProgram(self.program_adaptive),
-                _ => {}
-            }
+            let Ok(program) = self.tonemap.get(MODES[self.mode]) else {
+                return;
+            };
+            gl::UseProgram(program);
+            let name = std::ffi::CString::new("exposure").unwrap();
+            gl::Uniform1f(gl::GetUniformLocation(program, name.as_ptr()), self.exposure);
             gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
                 glfw::Key::Kp1 => self.mode = 0,
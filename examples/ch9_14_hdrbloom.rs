@@ -102,7 +102,7 @@ impl HDRBloomApp {
         }
     }
 
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         unsafe fn get_loc(prog: GLuint, name: &str) -> GLint {
             let name = std::ffi::CString::new(name).unwrap();
             gl::GetUniformLocation(prog, name.as_ptr())
@@ -114,12 +114,12 @@ impl HDRBloomApp {
             }
 
             let vs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-scene.vs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-scene.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-scene.fs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-scene.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -133,12 +133,12 @@ impl HDRBloomApp {
             }
 
             let vs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-filter.vs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-filter.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-filter.fs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-filter.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -149,12 +149,12 @@ impl HDRBloomApp {
             }
 
             let vs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-resolve.vs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-resolve.vs.glsl"),
                 gl::VERTEX_SHADER,
                 true,
             );
             let fs = shader::load(
-                "media/shaders/hdrbloom/hdrbloom-resolve.fs.glsl",
+                &context.media_path("media/shaders/hdrbloom/hdrbloom-resolve.fs.glsl"),
                 gl::FRAGMENT_SHADER,
                 true,
             );
@@ -175,14 +175,14 @@ impl Application for HDRBloomApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             let buffers = [gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1];
 
             gl::CreateVertexArrays(1, &mut self.vao);
             gl::BindVertexArray(self.vao);
 
-            self.load_shaders();
+            self.load_shaders(context);
 
             gl::GenFramebuffers(1, &mut self.render_fbo);
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.render_fbo);
@@ -244,9 +244,9 @@ impl Application for HDRBloomApp {
                 gl::DrawBuffers(1, &gl::COLOR_ATTACHMENT0);
             }
 
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, context.default_framebuffer());
 
-            self.object.load("media/objects/torus.sbm");
+            self.object.load(&context.media_path("media/objects/torus.sbm"));
 
             gl::GenBuffers(1, &mut self.ubo_transform);
             gl::BindBuffer(gl::UNIFORM_BUFFER, self.ubo_transform);
@@ -302,7 +302,7 @@ impl Application for HDRBloomApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteFramebuffers(1, &self.render_fbo);
             gl::DeleteFramebuffers(2, self.filter_fbo.as_ptr());
@@ -322,10 +322,10 @@ impl Application for HDRBloomApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0].as_ptr();
         let one = 1.0f32;
-        let info = self.info();
+        let info = context.info.clone();
 
         unsafe {
             static mut LAST_TIME: f64 = 0.0;
@@ -424,7 +424,7 @@ impl Application for HDRBloomApp {
                 );
             }
 
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, context.default_framebuffer());
             gl::ActiveTexture(gl::TEXTURE1);
             gl::BindTexture(gl::TEXTURE_2D, self.tex_filter[1]);
             gl::ActiveTexture(gl::TEXTURE0);
@@ -441,7 +441,7 @@ impl Application for HDRBloomApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
                 glfw::Key::B => self.show_bloom = !self.show_bloom,
@@ -452,7 +452,7 @@ impl Application for HDRBloomApp {
                 glfw::Key::X => self.bloom_thresh_min -= 0.1,
                 glfw::Key::D => self.bloom_thresh_max += 0.1,
                 glfw::Key::C => self.bloom_thresh_max -= 0.1,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 glfw::Key::N => self.show_prefilter = !self.show_prefilter,
                 glfw::Key::KpAdd => self.exposure *= 1.1,
                 glfw::Key::KpSubtract => self.exposure /= 1.1,
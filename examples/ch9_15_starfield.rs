@@ -48,7 +48,7 @@ impl Application for StarfieldApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let fs_source = cstring(
@@ -119,7 +119,7 @@ impl Application for StarfieldApp {
             let name = std::ffi::CString::new("proj_matrix").unwrap();
             self.uniforms.proj_matrix = gl::GetUniformLocation(self.render_prog, name.as_ptr());
 
-            self.star_texture = ktx::file::load("media/textures/star.ktx").unwrap().0;
+            self.star_texture = ktx::file::load(&context.media_path("media/textures/star.ktx")).unwrap().0;
 
             gl::GenVertexArrays(1, &mut self.star_vao);
             gl::BindVertexArray(self.star_vao);
@@ -182,11 +182,11 @@ impl Application for StarfieldApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 0.0f32].as_ptr();
         let one = [1.0f32].as_ptr();
         let mut t = current_time as f32;
-        let info = self.info();
+        let info = context.info.clone();
 
         let proj_matrix = perspective(50.0, info.width as f32 / info.height as f32, 0.1, 1000.0);
 
@@ -217,7 +217,7 @@ impl Application for StarfieldApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.render_prog);
             gl::DeleteVertexArrays(1, &self.star_vao);
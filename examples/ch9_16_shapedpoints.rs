@@ -35,7 +35,7 @@ impl Application for ShapedPointsApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
         let fs_source = cstring(
             r#"
@@ -113,7 +113,7 @@ impl Application for ShapedPointsApp {
         }
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         let black = [0.0, 0.0, 0.0, 0.0].as_ptr();
 
         unsafe {
@@ -127,7 +127,7 @@ impl Application for ShapedPointsApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.render_prog);
             gl::DeleteVertexArrays(1, &self.render_vao);
@@ -53,20 +53,20 @@ impl Application for BumpMappingApp {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
-            self.textures.color = ktx::file::load("media/textures/ladybug_co.ktx").unwrap().0;
+            self.textures.color = ktx::file::load(&context.media_path("media/textures/ladybug_co.ktx")).unwrap().0;
             gl::ActiveTexture(gl::TEXTURE1);
-            self.textures.normals = ktx::file::load("media/textures/ladybug_nm.ktx").unwrap().0;
+            self.textures.normals = ktx::file::load(&context.media_path("media/textures/ladybug_nm.ktx")).unwrap().0;
         }
 
-        self.object.load("media/objects/ladybug.sbm");
+        self.object.load(&context.media_path("media/objects/ladybug.sbm"));
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let gray = [0.1, 0.1, 0.1, 0.1].as_ptr();
         let ones = [1.0f32].as_ptr();
 
@@ -87,7 +87,7 @@ impl Application for BumpMappingApp {
 
             gl::UseProgram(self.program);
 
-            let info = self.info();
+            let info = context.info.clone();
             let proj_matrix =
                 perspective(50.0, info.width as f32 / info.height as f32, 0.1, 1000.0);
             gl::UniformMatrix4fv(
@@ -119,7 +119,7 @@ impl Application for BumpMappingApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         self.object.free();
         unsafe {
             gl::DeleteProgram(self.program);
@@ -128,11 +128,11 @@ impl Application for BumpMappingApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
-                glfw::Key::R => self.load_shaders(),
-                glfw::Key::S => self.make_screenshort(),
+                glfw::Key::R => self.load_shaders(context),
+                glfw::Key::S => self.make_screenshort(context),
                 glfw::Key::P => self.paused = !self.paused,
                 _ => {}
             }
@@ -141,14 +141,14 @@ impl Application for BumpMappingApp {
 }
 
 impl BumpMappingApp {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         let vs = shader::load(
-            "media/shaders/bumpmapping/bumpmapping.vs.glsl",
+            &context.media_path("media/shaders/bumpmapping/bumpmapping.vs.glsl"),
             gl::VERTEX_SHADER,
             true,
         );
         let fs = shader::load(
-            "media/shaders/bumpmapping/bumpmapping.fs.glsl",
+            &context.media_path("media/shaders/bumpmapping/bumpmapping.fs.glsl"),
             gl::FRAGMENT_SHADER,
             true,
         );
@@ -167,8 +167,8 @@ impl BumpMappingApp {
         self.uniforms.light_pos = get_location(self.program, "light_pos");
     }
 
-    fn make_screenshort(&self) {
-        let info = self.info();
+    fn make_screenshort(&self, context: &AppContext) {
+        let info = context.info.clone();
         let data_size = info.width * info.height * 3;
         let mut data = Box::new(vec![0u8; data_size]);
 
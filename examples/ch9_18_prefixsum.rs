@@ -49,7 +49,7 @@ impl Application for PrefixsumApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             gl::GenBuffers(2, self.data_buffer.as_mut_ptr());
 
@@ -76,10 +76,10 @@ impl Application for PrefixsumApp {
 
         Self::prefix_sum(&mut self.input_data, &mut self.output_data);
 
-        self.load_shaders();
+        self.load_shaders(context);
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         unsafe {
             gl::ClearBufferfv(gl::COLOR, 0, color::Black.as_ptr());
 
@@ -113,7 +113,7 @@ impl Application for PrefixsumApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         let ptr = unsafe {
             let ptr = gl::MapNamedBufferRange(
                 self.data_buffer[1],
@@ -129,10 +129,10 @@ impl Application for PrefixsumApp {
         unsafe { gl::UnmapNamedBuffer(self.data_buffer[1]) };
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
@@ -149,9 +149,9 @@ impl PrefixsumApp {
         }
     }
 
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         let cs = shader::load(
-            "media/shaders/prefixsum/prefixsum.cs.glsl",
+            &context.media_path("media/shaders/prefixsum/prefixsum.cs.glsl"),
             gl::COMPUTE_SHADER,
             true,
         );
@@ -39,11 +39,11 @@ impl Application for PrefixsumApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             gl::GenTextures(3, self.images.as_mut_ptr());
 
-            self.images[0] = ktx::file::load("media/textures/salad-gray.ktx").unwrap().0;
+            self.images[0] = ktx::file::load(&context.media_path("media/textures/salad-gray.ktx")).unwrap().0;
 
             for i in 1..3 {
                 gl::BindTexture(gl::TEXTURE_2D, self.images[i]);
@@ -60,10 +60,10 @@ impl Application for PrefixsumApp {
             gl::BindVertexArray(self.dummy_vao);
         }
 
-        self.load_shaders();
+        self.load_shaders(context);
     }
 
-    fn render(&mut self, _current_time: f64) {
+    fn render(&mut self, _context: &mut AppContext, _current_time: f64) {
         unsafe {
             gl::UseProgram(self.prefix_sum_prog);
 
@@ -93,10 +93,10 @@ impl Application for PrefixsumApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 _ => {}
             }
         }
@@ -113,9 +113,9 @@ impl PrefixsumApp {
         }
     }
 
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         let cs = shader::load(
-            "media/shaders/prefixsum2d/prefixsum2d.cs.glsl",
+            &context.media_path("media/shaders/prefixsum2d/prefixsum2d.cs.glsl"),
             gl::COMPUTE_SHADER,
             true,
         );
@@ -123,12 +123,12 @@ impl PrefixsumApp {
         self.prefix_sum_prog = program::link_from_shaders(&[cs], true);
 
         let vs = shader::load(
-            "media/shaders/prefixsum2d/showimage.vs.glsl",
+            &context.media_path("media/shaders/prefixsum2d/showimage.vs.glsl"),
             gl::VERTEX_SHADER,
             true,
         );
         let fs = shader::load(
-            "media/shaders/prefixsum2d/showimage.fs.glsl",
+            &context.media_path("media/shaders/prefixsum2d/showimage.fs.glsl"),
             gl::FRAGMENT_SHADER,
             true,
         );
@@ -45,7 +45,7 @@ impl Application for NoperspectiveApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_source = cstring(
@@ -173,7 +173,7 @@ impl Application for NoperspectiveApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
 
@@ -195,7 +195,7 @@ impl Application for NoperspectiveApp {
             let proj_matrix = perspective(
                 60.0,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1,
@@ -223,14 +223,14 @@ impl Application for NoperspectiveApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program);
             gl::DeleteVertexArrays(1, &self.vao);
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, _context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
                 glfw::Key::M => self.use_perspective = !self.use_perspective,
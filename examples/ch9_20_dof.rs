@@ -87,15 +87,15 @@ impl Application for DofApp {
         }
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         let object_names = [
-            "media/objects/dragon.sbm",
-            "media/objects/sphere.sbm",
-            "media/objects/cube.sbm",
-            "media/objects/cube.sbm",
-            "media/objects/cube.sbm",
+            &context.media_path("media/objects/dragon.sbm"),
+            &context.media_path("media/objects/sphere.sbm"),
+            &context.media_path("media/objects/cube.sbm"),
+            &context.media_path("media/objects/cube.sbm"),
+            &context.media_path("media/objects/cube.sbm"),
         ];
 
         let object_colors = [
@@ -151,7 +151,7 @@ impl Application for DofApp {
             gl::FramebufferTexture(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, self.color_tex, 0);
 
             gl::BindTexture(gl::TEXTURE_2D, 0);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, context.default_framebuffer());
 
             gl::Enable(gl::DEPTH_TEST);
 
@@ -160,8 +160,8 @@ impl Application for DofApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
-        let win_info = self.info();
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
+        let win_info = context.info.clone();
 
         unsafe {
             static mut TOTAL_TIME: f64 = 0.0;
@@ -212,7 +212,7 @@ impl Application for DofApp {
                 * translate(0.0, -4.0, 0.0f32);
 
             gl::Enable(gl::DEPTH_TEST);
-            self.render_scene();
+            self.render_scene(context);
 
             gl::UseProgram(self.filter_program);
 
@@ -273,7 +273,7 @@ impl Application for DofApp {
         }
     }
 
-    fn ui(&mut self, ui: &imgui::Ui) {
+    fn ui(&mut self, _context: &mut AppContext, ui: &imgui::Ui) {
         if let Some(win) = imgui::Window::new("Dof").always_auto_resize(true).begin(ui) {
             imgui::Slider::new("Focal distance", 0.0f32, 100.0f32)
                 .build(ui, &mut self.focal_distance);
@@ -288,14 +288,14 @@ impl Application for DofApp {
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press | glfw::Action::Repeat = press {
             match key {
                 glfw::Key::Q => self.focal_distance *= 1.1,
                 glfw::Key::A => self.focal_distance /= 1.1,
                 glfw::Key::W => self.focal_depth *= 1.1,
                 glfw::Key::S => self.focal_depth /= 1.1,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 glfw::Key::P => self.paused = !self.paused,
                 _ => {}
             }
@@ -304,8 +304,8 @@ impl Application for DofApp {
 }
 
 impl DofApp {
-    fn render_scene(&mut self) {
-        let win_info = self.info();
+    fn render_scene(&mut self, context: &AppContext) {
+        let win_info = context.info.clone();
         let gray = [0.1, 0.1, 0.1, 0.1f32].as_ptr();
         let ones = [1.0f32].as_ptr();
         let attachments = [gl::COLOR_ATTACHMENT0].as_ptr();
@@ -341,11 +341,11 @@ impl DofApp {
                 self.objects[0].obj.render();
             }
 
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, context.default_framebuffer());
         }
     }
 
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         fn get_loc(program: GLuint, name: &str) -> GLint {
             let name = std::ffi::CString::new(name).unwrap();
             unsafe { gl::GetUniformLocation(program, name.as_ptr()) }
@@ -357,9 +357,9 @@ impl DofApp {
 
         self.view_program = program::link_from_shaders(
             &[
-                shader::load("media/shaders/dof/render.vs.glsl", gl::VERTEX_SHADER, true),
+                shader::load(&context.media_path("media/shaders/dof/render.vs.glsl"), gl::VERTEX_SHADER, true),
                 shader::load(
-                    "media/shaders/dof/render.fs.glsl",
+                    &context.media_path("media/shaders/dof/render.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
@@ -378,9 +378,9 @@ impl DofApp {
 
         self.display_program = program::link_from_shaders(
             &[
-                shader::load("media/shaders/dof/display.vs.glsl", gl::VERTEX_SHADER, true),
+                shader::load(&context.media_path("media/shaders/dof/display.vs.glsl"), gl::VERTEX_SHADER, true),
                 shader::load(
-                    "media/shaders/dof/display.fs.glsl",
+                    &context.media_path("media/shaders/dof/display.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
@@ -397,7 +397,7 @@ impl DofApp {
 
         self.filter_program = program::link_from_shaders(
             &[shader::load(
-                "media/shaders/dof/gensat.cs.glsl",
+                &context.media_path("media/shaders/dof/gensat.cs.glsl"),
                 gl::COMPUTE_SHADER,
                 true,
             )],
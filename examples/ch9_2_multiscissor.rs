@@ -38,7 +38,7 @@ impl Application for MultiScissorApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -204,7 +204,7 @@ impl Application for MultiScissorApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
 
@@ -218,7 +218,7 @@ impl Application for MultiScissorApp {
             gl::Enable(gl::SCISSOR_TEST);
 
             // Each rectangle will be 7/16 of the screen
-            let AppConfig { width, height, .. } = self.info();
+            let AppConfig { width, height, .. } = context.info.clone();
             let viewport_width = (7 * width / 16) as i32;
             let viewport_height = (7 * height / 16) as i32;
 
@@ -280,7 +280,7 @@ impl Application for MultiScissorApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteProgram(self.program);
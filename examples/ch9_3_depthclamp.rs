@@ -38,7 +38,7 @@ impl Application for DepthclampApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_source = cstring(
@@ -111,7 +111,7 @@ impl Application for DepthclampApp {
             self.proj_location = get_loc("proj_matrix");
             self.explode_factor_location = get_loc("explode_factor");
 
-            self.object.load("media/objects/dragon.sbm");
+            self.object.load(&context.media_path("media/objects/dragon.sbm"));
 
             gl::Enable(gl::CULL_FACE);
 
@@ -120,7 +120,7 @@ impl Application for DepthclampApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let one = 1.0f32;
         let f = current_time as f32;
@@ -134,7 +134,7 @@ impl Application for DepthclampApp {
             let proj_matrix = perspective(
                 50.0,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1,
@@ -168,7 +168,7 @@ impl Application for DepthclampApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         self.object.free();
         unsafe { gl::DeleteProgram(self.program) };
     }
@@ -39,7 +39,7 @@ impl Application for BlendMatrixApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_source = cstring(
@@ -170,7 +170,7 @@ impl Application for BlendMatrixApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let orange = [0.6, 0.4, 0.1, 1.0f32].as_ptr();
         let one = 1.0f32;
         let t = current_time as f32;
@@ -208,7 +208,7 @@ impl Application for BlendMatrixApp {
             let proj_matrix = perspective(
                 50.0,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1,
@@ -243,7 +243,7 @@ impl Application for BlendMatrixApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program);
             gl::DeleteBuffers(1, &self.index_buffer);
@@ -46,7 +46,7 @@ impl Application for BasicFboApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_source = cstring(
@@ -296,14 +296,14 @@ impl Application for BasicFboApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let blue = [0.0, 0.0, 0.3, 1.0f32].as_ptr();
         let one = 1.0f32;
 
         let proj_matrix = perspective(
             50.0,
             {
-                let info = self.info();
+                let info = context.info.clone();
                 info.width as f32 / info.height as f32
             },
             0.1,
@@ -344,9 +344,9 @@ impl Application for BasicFboApp {
             );
             gl::DrawArrays(gl::TRIANGLES, 0, 36);
 
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, context.default_framebuffer());
 
-            let AppConfig { width, height, .. } = self.info();
+            let AppConfig { width, height, .. } = context.info.clone();
             gl::Viewport(0, 0, width as GLsizei, height as GLsizei);
             gl::ClearBufferfv(gl::DEPTH, 0, &one);
             gl::ClearBufferfv(gl::COLOR, 0, blue);
@@ -374,7 +374,7 @@ impl Application for BasicFboApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.vao);
             gl::DeleteProgram(self.program1);
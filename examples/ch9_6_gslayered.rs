@@ -43,14 +43,14 @@ impl Application for GsLayered {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, context: &mut AppContext) {
         unsafe {
             gl::GenVertexArrays(1, &mut self.vao);
             gl::BindVertexArray(self.vao);
 
-            self.load_shaders();
+            self.load_shaders(context);
 
-            self.obj.load("media/objects/torus.sbm");
+            self.obj.load(&context.media_path("media/objects/torus.sbm"));
 
             gl::GenBuffers(1, &mut self.transform_ubo);
             gl::BindBuffer(gl::UNIFORM_BUFFER, self.transform_ubo);
@@ -81,7 +81,7 @@ impl Application for GsLayered {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteProgram(self.program_gslayers);
             gl::DeleteProgram(self.program_showlayers);
@@ -92,12 +92,12 @@ impl Application for GsLayered {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
         let gray = [0.1, 0.1, 0.1, 1.0f32].as_ptr();
         let one = 1.0f32;
 
-        let info = self.info();
+        let info = context.info.clone();
         let t = current_time as f32;
 
         #[repr(C)]
@@ -141,8 +141,10 @@ impl Application for GsLayered {
 
             self.obj.render();
 
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
-            gl::DrawBuffer(gl::BACK);
+            // The offscreen framebuffer of headless runs has no back buffer
+            let fbo = context.default_framebuffer();
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::DrawBuffer(if fbo == 0 { gl::BACK } else { gl::COLOR_ATTACHMENT0 });
             gl::UseProgram(self.program_showlayers);
 
             gl::Viewport(0, 0, info.width as GLsizei, info.height as GLsizei);
@@ -160,7 +162,7 @@ impl Application for GsLayered {
 }
 
 impl GsLayered {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         if self.program_showlayers != 0 {
             unsafe { gl::DeleteProgram(self.program_showlayers) };
         }
@@ -168,12 +170,12 @@ impl GsLayered {
         self.program_showlayers = program::link_from_shaders(
             &[
                 shader::load(
-                    "media/shaders/gslayers/showlayers.vs.glsl",
+                    &context.media_path("media/shaders/gslayers/showlayers.vs.glsl"),
                     gl::VERTEX_SHADER,
                     true,
                 ),
                 shader::load(
-                    "media/shaders/gslayers/showlayers.fs.glsl",
+                    &context.media_path("media/shaders/gslayers/showlayers.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
@@ -188,17 +190,17 @@ impl GsLayered {
         self.program_gslayers = program::link_from_shaders(
             &[
                 shader::load(
-                    "media/shaders/gslayers/gslayers.vs.glsl",
+                    &context.media_path("media/shaders/gslayers/gslayers.vs.glsl"),
                     gl::VERTEX_SHADER,
                     true,
                 ),
                 shader::load(
-                    "media/shaders/gslayers/gslayers.gs.glsl",
+                    &context.media_path("media/shaders/gslayers/gslayers.gs.glsl"),
                     gl::GEOMETRY_SHADER,
                     true,
                 ),
                 shader::load(
-                    "media/shaders/gslayers/gslayers.fs.glsl",
+                    &context.media_path("media/shaders/gslayers/gslayers.fs.glsl"),
                     gl::FRAGMENT_SHADER,
                     true,
                 ),
@@ -91,14 +91,14 @@ impl Application for StereoApp {
         conf
     }
 
-    fn startup(&mut self) {
-        self.load_shaders();
+    fn startup(&mut self, context: &mut AppContext) {
+        self.load_shaders(context);
 
         let object_names = [
-            "media/objects/dragon.sbm",
-            "media/objects/sphere.sbm",
-            "media/objects/cube.sbm",
-            "media/objects/torus.sbm",
+            &context.media_path("media/objects/dragon.sbm"),
+            &context.media_path("media/objects/sphere.sbm"),
+            &context.media_path("media/objects/cube.sbm"),
+            &context.media_path("media/objects/torus.sbm"),
         ];
 
         for (i, object_name) in object_names.iter().enumerate() {
@@ -112,7 +112,7 @@ impl Application for StereoApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         // let zeros = [0.0, 0.0, 0.0, 0.0f32].as_ptr();
 
         unsafe {
@@ -124,7 +124,7 @@ impl Application for StereoApp {
             }
             LAST_TIME = current_time;
 
-            let info = self.info();
+            let info = context.info.clone();
             let f = TOTAL_TIME as f32 + 30.0;
 
             let light_position = vec3!(20.0, 20.0, 20.0);
@@ -168,17 +168,17 @@ impl Application for StereoApp {
 
             gl::Enable(gl::DEPTH_TEST);
 
-            self.render_scene(TOTAL_TIME);
+            self.render_scene(context, TOTAL_TIME);
         }
     }
 
-    fn on_key(&mut self, key: glfw::Key, press: glfw::Action) {
+    fn on_key(&mut self, context: &mut AppContext, key: glfw::Key, press: glfw::Action) {
         if let glfw::Action::Press = press {
             match key {
                 glfw::Key::Kp1 => self.mode = RenderMode::Full,
                 glfw::Key::Kp2 => self.mode = RenderMode::Light,
                 glfw::Key::Kp3 => self.mode = RenderMode::Depth,
-                glfw::Key::R => self.load_shaders(),
+                glfw::Key::R => self.load_shaders(context),
                 glfw::Key::P => self.paused = !self.paused,
                 _ => {}
             }
@@ -194,7 +194,7 @@ impl Application for StereoApp {
 }
 
 impl StereoApp {
-    fn load_shaders(&mut self) {
+    fn load_shaders(&mut self, context: &AppContext) {
         if self.view_program != 0 {
             unsafe { gl::DeleteProgram(self.view_program) };
         }
@@ -202,12 +202,12 @@ impl StereoApp {
         self.view_program = program::link_from_shaders(
             &[
                 shader::load(
-                    "media/shaders/stereo/stereo-render.vs.glsl",
+                    &context.media_path("media/shaders/stereo/stereo-render.vs.glsl"),
                     VERTEX_SHADER,
                     true,
                 ),
                 shader::load(
-                    "media/shaders/stereo/stereo-render.fs.glsl",
+                    &context.media_path("media/shaders/stereo/stereo-render.fs.glsl"),
                     FRAGMENT_SHADER,
                     true,
                 ),
@@ -228,11 +228,11 @@ impl StereoApp {
         self.uniforms.view.diffuse_albedo = get_loc(self.view_program, "diffuse_albedo");
     }
 
-    fn render_scene(&mut self, _current_time: f64) {
+    fn render_scene(&mut self, context: &AppContext, _current_time: f64) {
         let ones = [1.0f32].as_ptr();
         // let zero = [0.0f32];
         let gray = [0.1, 0.1, 0.1, 0.0f32].as_ptr();
-        let info = self.info();
+        let info = context.info.clone();
 
         let scale_basic_matrix = Mat4::from_vec([
             vec4!(0.5, 0.0, 0.0, 0.0),
@@ -256,7 +256,7 @@ impl StereoApp {
                 gl::FALSE,
                 addr_of!(self.camera_proj_matrix) as *const GLfloat,
             );
-            gl::DrawBuffer(gl::BACK);
+            gl::DrawBuffer(if context.default_framebuffer() == 0 { gl::BACK } else { gl::COLOR_ATTACHMENT0 });
         }
 
         let diffuse_colors = [
@@ -267,8 +267,12 @@ impl StereoApp {
         ];
 
         unsafe {
+            // Headless runs draw both eyes into the one offscreen attachment
+            let buffs = match context.default_framebuffer() {
+                0 => [gl::BACK_LEFT, gl::BACK_RIGHT],
+                _ => [gl::COLOR_ATTACHMENT0; 2],
+            };
             for j in 0..2 {
-                let buffs = [gl::BACK_LEFT, gl::BACK_RIGHT];
                 gl::DrawBuffer(buffs[j]);
                 gl::ClearBufferfv(gl::COLOR, 0, gray);
                 gl::ClearBufferfv(gl::DEPTH, 0, ones);
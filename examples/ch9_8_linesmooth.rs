@@ -41,7 +41,7 @@ impl Application for LinesmoothApp {
         }
     }
 
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let cstring = |str| std::ffi::CString::new(str).unwrap();
 
         let vs_src = cstring(
@@ -151,9 +151,9 @@ impl Application for LinesmoothApp {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let black = [0.0, 0.0, 0.0, 1.0f32].as_ptr();
-        let info = self.info();
+        let info = context.info.clone();
         let current_time = current_time as f32;
 
         unsafe {
@@ -218,7 +218,7 @@ impl Application for LinesmoothApp {
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
         unsafe {
             gl::DeleteBuffers(1, &self.index_buffer);
             gl::DeleteBuffers(1, &self.position_buffer);
@@ -23,37 +23,22 @@ use sb7::prelude::*;
 
 #[derive(Default)]
 struct App {
-    render_prog: u32,
-    border_prog: u32,
+    render_prog: program::Program,
+    border_prog: program::Program,
     vao: u32,
     vertices_buf: u32,
     indices_buf: u32,
-    uniforms: Uniforms,
-}
-
-#[derive(Default)]
-struct Uniforms {
-    mv_mat: i32,
-    proj_mat: i32,
 }
 
 impl Application for App {
-    fn startup(&mut self) {
+    fn startup(&mut self, _context: &mut AppContext) {
         let vs = shader::load("my_test/shaders/render.vert", gl::VERTEX_SHADER, true);
         let fs = shader::load("my_test/shaders/render.frag", gl::FRAGMENT_SHADER, true);
-        self.render_prog = program::link_from_shaders(&[vs, fs], true);
+        self.render_prog = program::Program::new(program::link_from_shaders(&[vs, fs], true));
 
         let vs = shader::load("my_test/shaders/render.vert", gl::VERTEX_SHADER, true);
         let fs = shader::load("my_test/shaders/border.frag", gl::FRAGMENT_SHADER, true);
-        self.border_prog = program::link_from_shaders(&[vs, fs], true);
-
-        fn get_loc(program: u32, name: &str) -> i32 {
-            let name = std::ffi::CString::new(name).unwrap();
-            unsafe { gl::GetUniformLocation(program, name.as_ptr()) }
-        }
-
-        self.uniforms.mv_mat = get_loc(self.render_prog, "mv_mat");
-        self.uniforms.proj_mat = get_loc(self.render_prog, "proj_mat");
+        self.border_prog = program::Program::new(program::link_from_shaders(&[vs, fs], true));
 
         #[rustfmt::skip]
         let vertex_indices: &[GLushort] = &[
@@ -112,7 +97,7 @@ impl Application for App {
         }
     }
 
-    fn render(&mut self, current_time: f64) {
+    fn render(&mut self, context: &mut AppContext, current_time: f64) {
         let t = current_time as f32;
         let black = [0.0, 0.0, 0.0, 0.0f32].as_ptr();
 
@@ -125,7 +110,7 @@ impl Application for App {
             let proj_mat = perspective(
                 50.0,
                 {
-                    let info = self.info();
+                    let info = context.info.clone();
                     info.width as f32 / info.height as f32
                 },
                 0.1,
@@ -141,37 +126,17 @@ impl Application for App {
                     vec3!(0.0, 1.0, 0.0),
                 ) * translate(i as f32 * 1.1, 0.0, j as f32 * 1.1);
 
-                gl::UseProgram(self.render_prog);
-                gl::UniformMatrix4fv(
-                    self.uniforms.mv_mat,
-                    1,
-                    gl::FALSE,
-                    addr_of!(mv_mat) as *const f32,
-                );
-                gl::UniformMatrix4fv(
-                    self.uniforms.proj_mat,
-                    1,
-                    gl::FALSE,
-                    addr_of!(proj_mat) as *const f32,
-                );
+                self.render_prog.use_program();
+                self.render_prog.set("mv_mat", &mv_mat);
+                self.render_prog.set("proj_mat", &proj_mat);
                 gl::StencilFunc(gl::ALWAYS, 1, 0xFF);
                 gl::StencilOp(gl::KEEP, gl::REPLACE, gl::REPLACE);
                 gl::DrawElements(gl::TRIANGLES, 36, gl::UNSIGNED_SHORT, null());
 
-                gl::UseProgram(self.border_prog);
+                self.border_prog.use_program();
                 let mv_mat = mv_mat * scale(1.3, 1.3, 1.3);
-                gl::UniformMatrix4fv(
-                    self.uniforms.mv_mat,
-                    1,
-                    gl::FALSE,
-                    addr_of!(mv_mat) as *const f32,
-                );
-                gl::UniformMatrix4fv(
-                    self.uniforms.proj_mat,
-                    1,
-                    gl::FALSE,
-                    addr_of!(proj_mat) as *const f32,
-                );
+                self.border_prog.set("mv_mat", &mv_mat);
+                self.border_prog.set("proj_mat", &proj_mat);
                 gl::StencilFunc(gl::GREATER, 1, 0xFF);
                 gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
                 gl::DrawElements(gl::TRIANGLES, 36, gl::UNSIGNED_SHORT, null());
@@ -185,32 +150,24 @@ impl Application for App {
 
             gl::Disable(gl::STENCIL);
 
-            gl::UseProgram(self.render_prog);
+            self.render_prog.use_program();
             let mv_mat = lookat(
                 vec3!(4.0 * t.cos(), 2.0, 4.0 * t.sin()),
                 vec3!(0.0, 0.0, 0.0),
                 vec3!(0.0, 1.0, 0.0),
             ) * scale(4.5, 0.2, 4.5);
-            gl::UniformMatrix4fv(
-                self.uniforms.mv_mat,
-                1,
-                gl::FALSE,
-                addr_of!(mv_mat) as *const f32,
-            );
-            gl::UniformMatrix4fv(
-                self.uniforms.proj_mat,
-                1,
-                gl::FALSE,
-                addr_of!(proj_mat) as *const f32,
-            );
+            self.render_prog.set("mv_mat", &mv_mat);
+            self.render_prog.set("proj_mat", &proj_mat);
             gl::DrawElements(gl::TRIANGLES, 36, gl::UNSIGNED_SHORT, null());
         }
     }
 
-    fn shutdown(&mut self) {
+    fn shutdown(&mut self, _context: &mut AppContext) {
+        // Delete the programs while the context is still current
+        self.render_prog = Default::default();
+        self.border_prog = Default::default();
+
         unsafe {
-            gl::DeleteProgram(self.border_prog);
-            gl::DeleteProgram(self.render_prog);
             gl::DeleteBuffers(1, &self.vertices_buf);
             gl::DeleteBuffers(1, &self.indices_buf);
         }
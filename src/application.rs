@@ -1,9 +1,22 @@
-use once_cell::sync::OnceCell;
+use std::error::Error;
+use std::fmt::Display;
+use std::path::PathBuf;
 
 use imgui_glfw_rs::glfw;
 use imgui_glfw_rs::imgui;
 
-use glfw::{Action, Context, Key};
+use glfw::{Action, Context, Key, MouseButton};
+
+use crate::clock::{Clock, TimeSource};
+use crate::config::{ConfigErr, Overrides, USAGE};
+use crate::debug::{DebugLog, DebugOutput, ResetStatus};
+use crate::headless::{ContextHints, HeadlessContext, HeadlessErr};
+use crate::prog::diagnostic::ShaderError;
+use crate::prog::reload::Reloader;
+use crate::program::{self, ProgramCache};
+use crate::record::{Recorder, Recording};
+use crate::screenshot;
+use crate::stats::FrameStats;
 
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -11,8 +24,23 @@ pub struct AppConfig {
     pub width: usize,
     pub height: usize,
     pub flags: AppFlags,
-    pub glfw: glfw::Glfw,
     pub samples: u32,
+    /// Number of frames to render before `run()` returns, `0` means until the
+    /// window is closed. Headless runs always render at least one frame.
+    pub frames: u32,
+    /// Supplies the `current_time` passed to `render`.
+    pub clock: Clock,
+    /// Directory that `media/...` paths resolve to, see `AppContext::assets`.
+    pub media_dir: String,
+    /// Where shaders look for `#include`d files they don't have next to them.
+    pub include_dirs: Vec<String>,
+    /// Directory of the program binary cache used by `AppContext::builder`,
+    /// no binaries are cached when `None`.
+    pub program_cache: Option<String>,
+    /// Filtering and destination of GL debug messages when `flags.debug` is set.
+    pub debug_output: DebugOutput,
+    /// Write every rendered frame to disk.
+    pub record: Option<Recording>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -21,8 +49,14 @@ pub struct AppFlags {
     pub vsync: bool,
     pub cursor: bool,
     pub stereo: bool,
+    /// Request a debug context and forward its messages to `debug_output`.
     pub debug: bool,
+    /// Request a robust context and report a lost context via `on_context_lost`.
     pub robust: bool,
+    /// Render into an offscreen framebuffer through a surfaceless EGL or an
+    /// OSMesa context, without GLFW or a display. Apps that bind framebuffer
+    /// 0 should bind `AppContext::default_framebuffer()` instead.
+    pub headless: bool,
 }
 
 impl Default for AppConfig {
@@ -32,131 +66,615 @@ impl Default for AppConfig {
             width: 800,
             height: 600,
             flags: Default::default(),
-            glfw: glfw::init(glfw::FAIL_ON_ERRORS).unwrap(),
             samples: 0,
+            frames: 0,
+            clock: Default::default(),
+            media_dir: String::from("media"),
+            include_dirs: vec![String::from("media/shaders")],
+            program_cache: None,
+            debug_output: Default::default(),
+            record: None,
+        }
+    }
+}
+
+/// Where the loaders find their files. Loaders that aren't given one use
+/// `Assets::default()`, which leaves `media/...` paths as they are.
+#[derive(Debug, Clone)]
+pub struct Assets {
+    /// Directory that `media/...` paths resolve to.
+    pub media_dir: PathBuf,
+    /// Include search paths, with `media/...` already resolved.
+    pub include_dirs: Vec<PathBuf>,
+    /// Cache for the programs linked from files, none when `None`.
+    pub program_cache: Option<ProgramCache>,
+}
+
+impl Assets {
+    /// The `media_dir`, `include_dirs` and `program_cache` of `info`.
+    pub fn new(info: &AppConfig) -> Self {
+        let mut assets = Self {
+            media_dir: PathBuf::from(&info.media_dir),
+            include_dirs: Vec::new(),
+            program_cache: info.program_cache.as_ref().map(ProgramCache::new),
+        };
+        assets.include_dirs = info.include_dirs.iter().map(|dir| assets.path(dir)).collect();
+        assets
+    }
+
+    /// Map a `media/...` path onto `media_dir`, other paths are returned
+    /// unchanged.
+    pub fn path(&self, path: &str) -> PathBuf {
+        match path.strip_prefix("media/") {
+            Some(rest) => self.media_dir.join(rest),
+            None => PathBuf::from(path),
+        }
+    }
+}
+
+impl Default for Assets {
+    fn default() -> Self {
+        Self::new(&AppConfig::default())
+    }
+}
+
+/// OpenGL version requested for the window's context.
+const GL_VERSION: (u32, u32) = (4, 6);
+
+/// Why `Application::try_run` couldn't run the application.
+#[derive(Debug)]
+pub enum RunErr
+{
+    InitErr(glfw::InitError),
+    HeadlessErr(HeadlessErr),
+    ConfigErr(ConfigErr),
+    WindowErr,
+    /// The driver only offers an older context than `GL_VERSION`.
+    ContextVersionErr((u32, u32)),
+    /// `AppConfig::record` asks for 0 frames per second.
+    RecordFpsErr,
+    StartupErr(Box<dyn Error>),
+}
+
+impl Display for RunErr
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self
+        {
+            Self::InitErr(err) => write!(f, "Failed to initialize GLFW: {}", err),
+            Self::HeadlessErr(err) => write!(f, "{}", err),
+            Self::ConfigErr(err) => write!(f, "{}", err),
+            Self::WindowErr => write!(f, "Failed to create a window with an OpenGL context"),
+            Self::ContextVersionErr((major, minor)) => write!(f,
+                "OpenGL {}.{} is required, but only {}.{} is supported",
+                GL_VERSION.0, GL_VERSION.1, major, minor),
+            Self::RecordFpsErr => write!(f, "Recording needs at least 1 frame per second"),
+            Self::StartupErr(err) => write!(f, "Startup failed: {}", err),
+        }
+    }
+}
+
+impl Error for RunErr
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self
+        {
+            Self::HeadlessErr(e) => Some(e),
+            Self::ConfigErr(e) => Some(e),
+            Self::StartupErr(e) => Some(e.as_ref()),
+            _ => None,
         }
     }
 }
 
-static mut INFO: OnceCell<AppConfig> = OnceCell::new();
+/// Work out why no window could be created with the requested hints. If a
+/// plain context can still be made, its version tells whether the driver is
+/// simply too old.
+fn window_err(glfw: &mut glfw::Glfw) -> RunErr {
+    glfw.default_window_hints();
+    glfw.window_hint(glfw::WindowHint::Visible(false));
+    match glfw.create_window(1, 1, "", glfw::WindowMode::Windowed) {
+        Some((window, _)) => {
+            let version = window.get_context_version();
+            let version = (version.major as u32, version.minor as u32);
+            match version < GL_VERSION {
+                true => RunErr::ContextVersionErr(version),
+                false => RunErr::WindowErr,
+            }
+        }
+        None => RunErr::WindowErr,
+    }
+}
+
+/// Framebuffer that stands in for the window's back buffer in headless mode.
+#[derive(Debug)]
+struct Offscreen {
+    fbo: u32,
+    color: u32,
+    depth: u32,
+    width: i32,
+    height: i32,
+}
+
+impl Offscreen {
+    fn new(width: i32, height: i32, samples: i32) -> Self {
+        let mut offscreen = Self { fbo: 0, color: 0, depth: 0, width, height };
+        super::gl! {
+            gl::GenRenderbuffers(1, &mut offscreen.color);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, offscreen.color);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::RGBA8, width, height);
+
+            gl::GenRenderbuffers(1, &mut offscreen.depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, offscreen.depth);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::DEPTH24_STENCIL8, width, height);
+
+            gl::GenFramebuffers(1, &mut offscreen.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, offscreen.fbo);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, offscreen.color);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, offscreen.depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+        offscreen
+    }
+
+    fn bind(&self) {
+        super::gl!(gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo));
+    }
+}
+
+impl Drop for Offscreen {
+    fn drop(&mut self) {
+        super::gl! {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteRenderbuffers(1, &self.color);
+            gl::DeleteRenderbuffers(1, &self.depth);
+        }
+    }
+}
+
+/// Whether imgui wants `event` for itself, in which case the app doesn't get it.
+fn captured_by_imgui(io: &imgui::Io, event: &glfw::WindowEvent) -> bool {
+    match event {
+        glfw::WindowEvent::MouseButton(..)
+        | glfw::WindowEvent::CursorPos(..)
+        | glfw::WindowEvent::Scroll(..) => io.want_capture_mouse,
+        glfw::WindowEvent::Char(..) => io.want_text_input,
+        // The run loop's own hotkeys work even while a text field has focus
+        glfw::WindowEvent::Key(Key::Escape | Key::F3 | Key::F9 | Key::F10 | Key::F12, ..) => false,
+        glfw::WindowEvent::Key(..) => io.want_capture_keyboard,
+        _ => false,
+    }
+}
+
+/// State owned by a single `Application::run` and passed to every callback.
+/// Each run starts from a fresh context, so several applications can run
+/// one after another in the same process.
+#[derive(Debug)]
+pub struct AppContext {
+    pub info: AppConfig,
+    /// Timings of the last frames, the overlay is toggled with F3.
+    pub stats: FrameStats,
+    /// `None` in headless mode.
+    glfw: Option<glfw::Glfw>,
+    framebuffer_size: (i32, i32),
+    offscreen: Option<Offscreen>,
+    screenshots: Vec<screenshot::Request>,
+    reloader: Reloader,
+}
+
+impl AppContext {
+    fn new(info: AppConfig, glfw: Option<glfw::Glfw>) -> Self {
+        Self {
+            framebuffer_size: (info.width as _, info.height as _),
+            info,
+            glfw,
+            stats: Default::default(),
+            offscreen: None,
+            screenshots: Vec::new(),
+            reloader: Default::default(),
+        }
+    }
+
+    /// Framebuffer standing in for the window in headless mode. It has the
+    /// size of `info`, and is resized before the next frame when that changes.
+    pub fn offscreen_fbo(&self) -> Option<u32> {
+        self.offscreen.as_ref().map(|offscreen| offscreen.fbo)
+    }
+
+    /// Framebuffer the app should bind to draw to the window: the offscreen
+    /// one in headless mode, 0 otherwise. Nothing drawn to framebuffer 0 in
+    /// headless mode ends up in screenshots or recordings.
+    pub fn default_framebuffer(&self) -> u32 {
+        self.offscreen_fbo().unwrap_or(0)
+    }
+
+    /// Size of the default framebuffer in pixels. It differs from the window
+    /// size of `info` on HiDPI displays.
+    pub fn framebuffer_size(&self) -> (i32, i32) {
+        self.framebuffer_size
+    }
+
+    /// Save `source` to `path` once the current frame has been rendered, the
+    /// file format follows the extension (`.png`, `.ppm` or `.hdr`).
+    pub fn screenshot<P: Into<PathBuf>>(&mut self, path: P, source: screenshot::Source) {
+        self.screenshots.push(screenshot::Request { path: path.into(), source });
+    }
+
+    pub fn set_vsync(&mut self, enable: bool) {
+        self.info.flags.vsync = enable;
+        if let Some(glfw) = &mut self.glfw {
+            glfw.set_swap_interval(glfw::SwapInterval::Sync(if enable { 1 } else { 0 }));
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.info.clock.set_paused(paused);
+    }
+
+    /// Pause the clock and render exactly one more frame.
+    pub fn step_frame(&mut self) {
+        self.info.clock.step();
+    }
+
+    /// Where the loaders should look for files according to `info`.
+    pub fn assets(&self) -> Assets {
+        Assets::new(&self.info)
+    }
+
+    /// Map a `media/...` path onto `info.media_dir`, for loaders that take a
+    /// plain path such as `Object::load` or `ktx::file::load`.
+    pub fn media_path(&self, path: &str) -> String {
+        self.assets().path(path).to_string_lossy().into_owned()
+    }
+
+    /// A `program::Builder` that loads its files through `assets`.
+    pub fn builder(&self) -> program::Builder {
+        program::Builder::new().assets(&self.assets())
+    }
+
+    /// Build a program and rebuild it the same way whenever one of its files
+    /// changes while the application runs. The new program replaces the old
+    /// one only if it links, see `Application::on_program_reload`.
+    pub fn load_watched(&mut self, builder: program::Builder) -> Result<u32, ShaderError> {
+        let (program, files) = builder.build_with_files()?;
+        self.reloader.watch(program, builder, files);
+        Ok(program)
+    }
+
+    /// Stop rebuilding a program from `load_watched`.
+    pub fn unwatch(&mut self, program: u32) {
+        self.reloader.unwatch(program);
+    }
+}
 
 pub trait Application {
     fn init(&self) -> AppConfig {
         AppConfig::default()
     }
 
-    fn info(&self) -> AppConfig {
-        unsafe { INFO.get().unwrap().clone() }
+    fn ui(&mut self, _context: &mut AppContext, _ui: &imgui::Ui) {}
+
+    /// Run the application until its window is closed, printing the error
+    /// and exiting if it can't be started.
+    fn run(&mut self) {
+        match self.try_run() {
+            Ok(()) => {}
+            Err(RunErr::ConfigErr(ConfigErr::HelpRequested)) => println!("{}", USAGE),
+            Err(err @ RunErr::ConfigErr(_)) => {
+                eprintln!("{}\n\n{}", err, USAGE);
+                std::process::exit(2);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
     }
 
-    fn ui(&mut self, _ui: &imgui::Ui) {}
+    /// `try_run_with` the overrides from the command line and config file.
+    fn try_run(&mut self) -> Result<(), RunErr> {
+        let overrides = Overrides::load().map_err(RunErr::ConfigErr)?;
+        self.try_run_with(&overrides)
+    }
 
-    fn run(&mut self) {
-        let info = unsafe {
-            INFO.set(self.init()).unwrap();
-            INFO.get_mut().unwrap()
+    /// Run with `overrides` layered over `init`, without reading the command
+    /// line or a config file.
+    fn try_run_with(&mut self, overrides: &Overrides) -> Result<(), RunErr> {
+        let mut info = self.init();
+        overrides.apply(&mut info);
+        if let Some(record) = &info.record {
+            if record.fps == 0 {
+                return Err(RunErr::RecordFpsErr);
+            }
+            // Frames are played back at `fps`, so they have to be rendered
+            // that far apart too
+            if !matches!(info.clock.source(), TimeSource::Scripted(_)) {
+                info.clock.set_source(TimeSource::FixedStep(1.0 / record.fps as f64));
+            }
+        }
+
+        // GLFW needs a display even for hidden windows, headless runs go
+        // without it
+        let glfw = match info.flags.headless {
+            true => None,
+            false => Some(glfw::init(glfw::LOG_ERRORS).map_err(RunErr::InitErr)?),
         };
 
-        let glfw = &mut info.glfw;
-        glfw.window_hint(glfw::WindowHint::ContextVersion(4, 6));
-        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(
-            glfw::OpenGlProfileHint::Core,
-        ));
-        glfw.window_hint(glfw::WindowHint::Stereo(info.flags.stereo));
-        glfw.window_hint(glfw::WindowHint::Samples(Some(info.samples)));
-
-        let (mut window, events) = glfw
-            .clone()
-            .with_primary_monitor(|_, m| {
-                glfw.create_window(
-                    info.width as u32,
-                    info.height as u32,
-                    &info.title,
-                    match info.flags.fullscreen {
-                        true => m.map_or(glfw::WindowMode::Windowed, |m| {
-                            glfw::WindowMode::FullScreen(m)
-                        }),
-                        false => glfw::WindowMode::Windowed,
-                    },
-                )
-            })
-            .expect("Failed to create GLFW window.");
-
-        gl::load_with(|s| window.get_proc_address(s));
-
-        let mut imgui = imgui::Context::create();
-        imgui.set_ini_filename(None);
-        let mut imgui_glfw = imgui_glfw_rs::ImguiGLFW::new(&mut imgui, &mut window);
+        let mut context = AppContext::new(info, glfw.clone());
+        let info = context.info.clone();
+
+        let mut headless = None;
+        let mut windowed = None;
+        match glfw {
+            None => {
+                let hints = ContextHints {
+                    version: GL_VERSION,
+                    debug: info.flags.debug,
+                    robust: info.flags.robust,
+                    width: info.width as u32,
+                    height: info.height as u32,
+                };
+                let headless_context = HeadlessContext::new(hints).map_err(RunErr::HeadlessErr)?;
+                gl::load_with(|s| headless_context.get_proc_address(s));
+                headless = Some(headless_context);
+            }
+            Some(mut glfw) => {
+                glfw.window_hint(glfw::WindowHint::ContextVersion(GL_VERSION.0, GL_VERSION.1));
+                glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+                glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+                    glfw::OpenGlProfileHint::Core,
+                ));
+                glfw.window_hint(glfw::WindowHint::Stereo(info.flags.stereo));
+                glfw.window_hint(glfw::WindowHint::Samples(Some(info.samples)));
+                glfw.window_hint(glfw::WindowHint::OpenGlDebugContext(info.flags.debug));
+                glfw.window_hint(glfw::WindowHint::ContextRobustness(match info.flags.robust {
+                    true => glfw::ContextRobustnessHint::LoseContextOnReset,
+                    false => glfw::ContextRobustnessHint::NoRobustness,
+                }));
+
+                let (mut window, events) = glfw
+                    .clone()
+                    .with_primary_monitor(|_, m| {
+                        glfw.create_window(
+                            info.width as u32,
+                            info.height as u32,
+                            &info.title,
+                            match info.flags.fullscreen {
+                                true => m.map_or(glfw::WindowMode::Windowed, |m| {
+                                    glfw::WindowMode::FullScreen(m)
+                                }),
+                                false => glfw::WindowMode::Windowed,
+                            },
+                        )
+                    })
+                    .ok_or_else(|| window_err(&mut glfw))?;
+
+                gl::load_with(|s| window.get_proc_address(s));
+
+                let mut imgui = imgui::Context::create();
+                imgui.set_ini_filename(None);
+                let imgui_glfw = imgui_glfw_rs::ImguiGLFW::new(&mut imgui, &mut window);
+
+                window.set_all_polling(true);
+                window.make_current();
+                context.framebuffer_size = window.get_framebuffer_size();
+                windowed = Some((glfw, window, events, imgui, imgui_glfw));
+            }
+        }
 
         super::gl! {
           gl::Viewport(0, 0, info.width as i32, info.height as i32);
         }
+        context.set_vsync(info.flags.vsync);
+
+        let debug_log = info.flags.debug.then(|| DebugLog::install(info.debug_output));
+
+        if headless.is_some() {
+            context.offscreen = Some(Offscreen::new(info.width as _, info.height as _, info.samples as _));
+        }
+        let frames = match (headless.is_some(), info.frames) {
+            (true, 0) => 1,
+            (_, frames) => frames,
+        };
+
+        let mut recorder = info.record.clone().map(Recorder::new);
+
+        if let Err(err) = self.try_startup(&mut context) {
+            context.offscreen = None;
+            return Err(RunErr::StartupErr(err));
+        }
+
+        let start = std::time::Instant::now();
+        let mut frame = 0;
+        while !windowed.as_ref().is_some_and(|(_, window, ..)| window.should_close())
+            && (frames == 0 || frame < frames)
+        {
+            if let Some((glfw, window, events, imgui, imgui_glfw)) = &mut windowed {
+                glfw.poll_events();
+                for (_, event) in glfw::flush_messages(events) {
+                    imgui_glfw.handle_event(imgui, &event);
+                    if !captured_by_imgui(imgui.io(), &event) {
+                        self.handle_window_event(&mut context, window, event);
+                    }
+                }
+            }
+
+            for (old, new) in context.reloader.poll() {
+                if self.on_program_reload(&mut context, old, new) {
+                    super::gl!(gl::DeleteProgram(old));
+                } else {
+                    eprintln!("Program {} is still in use, see Application::on_program_reload", old);
+                    context.reloader.revert(new, old);
+                    super::gl!(gl::DeleteProgram(new));
+                }
+            }
+
+            // Follow changes of `info.width` and `info.height` in headless mode
+            let size = (context.info.width as i32, context.info.height as i32);
+            let mut resized = false;
+            if let Some(offscreen) = &mut context.offscreen {
+                if (offscreen.width, offscreen.height) != size {
+                    *offscreen = Offscreen::new(size.0, size.1, context.info.samples as _);
+                    context.framebuffer_size = size;
+                    resized = true;
+                }
+                offscreen.bind();
+            }
+            if resized {
+                super::gl!(gl::Viewport(0, 0, size.0, size.1));
+                self.on_resize(&mut context, size.0, size.1);
+            }
 
-        window.set_all_polling(true);
-        window.make_current();
+            let time = |windowed: &Option<(glfw::Glfw, _, _, _, _)>| match windowed {
+                Some((glfw, ..)) => glfw.get_time(),
+                None => start.elapsed().as_secs_f64(),
+            };
+            let now = time(&windowed);
+            context.stats.begin_frame(now);
+            let current_time = context.info.clock.tick(now);
+            self.render(&mut context, current_time);
 
-        self.startup();
+            for request in std::mem::take(&mut context.screenshots) {
+                request.take(&context);
+            }
 
-        while !window.should_close() {
-            glfw.poll_events();
-            for (_, event) in glfw::flush_messages(&events) {
-                imgui_glfw.handle_event(&mut imgui, &event);
-                self.handle_window_event(&mut window, event);
+            if let Some(Err(err)) = recorder.as_mut().map(|recorder| recorder.capture(&context)) {
+                eprintln!("Recording stopped: {}", err);
+                recorder = None;
             }
 
-            self.render(glfw.get_time());
+            if let Some((_, window, _, imgui, imgui_glfw)) = &mut windowed {
+                let ui = imgui_glfw.frame(window, imgui);
 
-            let ui = imgui_glfw.frame(&mut window, &mut imgui);
+                self.ui(&mut context, &ui);
+                if context.stats.show_overlay {
+                    context.stats.draw_overlay(&ui);
+                }
 
-            self.ui(&ui);
+                imgui_glfw.draw(ui, window);
+            }
 
-            imgui_glfw.draw(ui, &mut window);
+            let now = time(&windowed);
+            context.stats.end_frame(now);
+
+            match &mut windowed {
+                Some((_, window, ..)) => window.swap_buffers(),
+                None => super::gl!(gl::Finish()),
+            }
 
-            window.swap_buffers();
+            frame += 1;
+
+            if info.flags.robust {
+                if let Some(status) = ResetStatus::poll() {
+                    self.on_context_lost(&mut context, status);
+                    break;
+                }
+            }
+        }
+
+        match recorder.map(Recorder::finish) {
+            Some(Ok(frames)) => println!("Recorded {} frames", frames),
+            Some(Err(err)) => eprintln!("Failed to finish recording: {}", err),
+            None => {}
         }
 
-        self.shutdown();
+        self.shutdown(&mut context);
+        context.stats.free();
+        context.offscreen = None;
+        drop(debug_log);
+        drop(headless);
+        Ok(())
     }
 
-    fn startup(&mut self) {}
-    fn render(&mut self, current_time: f64) {
+    fn startup(&mut self, _context: &mut AppContext) {}
+
+    /// Fallible version of `startup`, an error ends `try_run` with
+    /// `RunErr::StartupErr`. Calls `startup` unless overridden.
+    fn try_startup(&mut self, context: &mut AppContext) -> Result<(), Box<dyn Error>> {
+        self.startup(context);
+        Ok(())
+    }
+    fn render(&mut self, _context: &mut AppContext, current_time: f64) {
         super::gl! {
           let g = (current_time.sin() * 0.5 + 0.5) as f32;
           gl::ClearBufferfv(gl::COLOR, 0, [g, g, g, 1.0f32].as_ptr());
         }
     }
-    fn shutdown(&mut self) {}
+    fn shutdown(&mut self, _context: &mut AppContext) {}
+
+    fn on_resize(&mut self, _context: &mut AppContext, _w: i32, _h: i32) {}
+
+    fn on_key(&mut self, _context: &mut AppContext, _key: Key, _press: Action) {}
 
-    fn on_resize(&mut self, _w: i32, _h: i32) {}
+    fn on_mouse_button(&mut self, _context: &mut AppContext, _button: MouseButton, _action: Action) {}
 
-    fn on_key(&mut self, _key: Key, _press: Action) {}
+    fn on_mouse_move(&mut self, _context: &mut AppContext, _x: f64, _y: f64) {}
 
-    fn handle_window_event(&mut self, window: &mut glfw::Window, event: glfw::WindowEvent) {
+    fn on_mouse_wheel(&mut self, _context: &mut AppContext, _x: f64, _y: f64) {}
+
+    fn on_char(&mut self, _context: &mut AppContext, _c: char) {}
+
+    fn on_focus(&mut self, _context: &mut AppContext, _focused: bool) {}
+
+    /// A program from `AppContext::load_watched` was rebuilt after its files
+    /// changed. Switch over to `new`, query its uniforms again and return
+    /// `true`, `old` is then deleted right after this returns. Returning
+    /// `false` keeps `old` and deletes `new` instead, see `Program::on_reload`.
+    fn on_program_reload(&mut self, _context: &mut AppContext, _old: u32, _new: u32) -> bool {
+        false
+    }
+
+    /// Called once when a robust context has been reset, the run loop stops
+    /// right after since nothing drawn to the lost context will show up.
+    fn on_context_lost(&mut self, _context: &mut AppContext, status: ResetStatus) {
+        eprintln!("OpenGL context lost: {:?}", status);
+    }
+
+    fn handle_window_event(
+        &mut self,
+        context: &mut AppContext,
+        window: &mut glfw::Window,
+        event: glfw::WindowEvent,
+    ) {
         match event {
             glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                 window.set_should_close(true)
             }
+            glfw::WindowEvent::Key(Key::F3, _, Action::Press, _) => {
+                context.stats.show_overlay ^= true;
+            }
+            glfw::WindowEvent::Key(Key::F12, _, Action::Press, _) => {
+                let path = format!("screenshot_{:05}.png", context.info.clock.frame());
+                context.screenshot(path, screenshot::Source::Frame);
+            }
+            glfw::WindowEvent::Key(Key::F9, _, Action::Press, _) => {
+                let paused = context.info.clock.is_paused();
+                context.set_paused(!paused);
+            }
+            glfw::WindowEvent::Key(Key::F10, _, Action::Press | Action::Repeat, _) => {
+                context.step_frame();
+            }
             glfw::WindowEvent::Key(key, _, action, _) => {
-                self.on_key(key, action);
-            }
-            glfw::WindowEvent::Size(w, h) => unsafe {
-                gl::Viewport(0, 0, w, h);
-                {
-                    let info = INFO.get_mut().unwrap();
-                    info.width = w as _;
-                    info.height = h as _;
-                }
-                self.on_resize(w, h);
-            },
+                self.on_key(context, key, action);
+            }
+            glfw::WindowEvent::Size(w, h) => {
+                super::gl!(gl::Viewport(0, 0, w, h));
+                context.info.width = w as _;
+                context.info.height = h as _;
+                self.on_resize(context, w, h);
+            }
+            glfw::WindowEvent::FramebufferSize(w, h) => {
+                context.framebuffer_size = (w, h);
+            }
+            glfw::WindowEvent::MouseButton(button, action, _) => {
+                self.on_mouse_button(context, button, action);
+            }
+            glfw::WindowEvent::CursorPos(x, y) => self.on_mouse_move(context, x, y),
+            glfw::WindowEvent::Scroll(x, y) => self.on_mouse_wheel(context, x, y),
+            glfw::WindowEvent::Char(c) => self.on_char(context, c),
+            glfw::WindowEvent::Focus(focused) => self.on_focus(context, focused),
             _ => {}
         }
     }
-
-    fn set_vsync(&mut self, enable: bool) {
-        let info = unsafe { INFO.get_mut().unwrap() };
-        info.flags.vsync = enable;
-        info.glfw
-            .set_swap_interval(glfw::SwapInterval::Sync(if enable { 1 } else { 0 }));
-    }
 }
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use sb7::glsl::{check_stages, find_files, Interface};
+use sb7::include::Preprocessor;
+use sb7::shader::Stage;
+
+const USAGE: &str = "\
+Usage: glslcheck [--quiet] [dir...]
+
+Parse every .glsl file under each dir (media/shaders by default) without a
+GL context, print what each shader declares and report syntax errors, bad
+#versions and mismatched interfaces between the stages of a program.
+
+  -q, --quiet   Only print problems";
+
+/// `dir/name.vs.glsl` belongs to program `dir/name`.
+fn program_name(path: &Path) -> PathBuf {
+  let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+  let name = name.split('.').next().unwrap_or_default();
+  path.with_file_name(name)
+}
+
+/// Check the shaders under `root`, returning the number of errors.
+fn check_dir(root: &Path, quiet: bool) -> usize {
+  let preprocessor = Preprocessor::new(vec![root.into()]);
+  let mut errors = 0;
+  let mut programs: BTreeMap<PathBuf, Vec<(Stage, Interface)>> = BTreeMap::new();
+
+  for path in find_files(root) {
+    // Files without a stage suffix are includes, checked by their includers
+    let Some(stage) = Stage::from_path(&path) else { continue };
+
+    let source = match preprocessor.load(&path) {
+      Ok(source) => source,
+      Err(err) => {
+        println!("{}", err);
+        errors += 1;
+        continue;
+      }
+    };
+    let interface = Interface::parse(&source, Some(stage));
+
+    if !quiet {
+      let version = interface.version.as_ref().map_or(String::from("no #version"), |version| {
+        format!("#version {} {}", version.number, version.profile.as_deref().unwrap_or_default())
+      });
+      println!("{}: {}, {}", path.display(), stage, version.trim_end());
+      interface.declarations.iter().for_each(|declaration| println!("  {}", declaration));
+      interface.blocks.iter().for_each(|block| println!("  {}", block));
+    }
+    for diagnostic in &interface.diagnostics {
+      println!("{}", diagnostic);
+    }
+    errors += interface.has_errors() as usize;
+
+    programs.entry(program_name(&path)).or_default().push((stage, interface));
+  }
+
+  for (program, mut stages) in programs {
+    stages.retain(|(stage, interface)| *stage != Stage::Compute && !interface.has_errors());
+    stages.sort_by_key(|(stage, _)| Stage::ALL.iter().position(|s| s == stage));
+    for pair in stages.windows(2) {
+      let ((from, from_interface), (to, to_interface)) = (&pair[0], &pair[1]);
+      if let Err(err) = check_stages((*from, from_interface), (*to, to_interface)) {
+        println!("{}: {}", program.display(), err);
+        errors += 1;
+      }
+    }
+  }
+  errors
+}
+
+fn main() {
+  let mut quiet = false;
+  let mut dirs = Vec::new();
+  for arg in std::env::args().skip(1) {
+    match arg.as_str() {
+      "-q" | "--quiet" => quiet = true,
+      "-h" | "--help" => {
+        println!("{}", USAGE);
+        return;
+      }
+      _ if arg.starts_with('-') => {
+        eprintln!("Unknown option `{}`\n\n{}", arg, USAGE);
+        std::process::exit(2);
+      }
+      _ => dirs.push(PathBuf::from(arg)),
+    }
+  }
+  if dirs.is_empty() {
+    dirs.push("media/shaders".into());
+  }
+
+  let errors: usize = dirs.iter().map(|dir| check_dir(dir, quiet)).sum();
+  if errors > 0 {
+    println!("{} error(s)", errors);
+    std::process::exit(1);
+  }
+}
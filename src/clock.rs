@@ -0,0 +1,98 @@
+/// Where the `current_time` passed to `Application::render` comes from.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum TimeSource {
+  /// Wall-clock seconds, excluding the time spent paused.
+  #[default]
+  RealTime,
+  /// Advance by a fixed number of seconds per frame, however long it took.
+  FixedStep(f64),
+  /// Replay a list of timestamps, holding the last one once exhausted.
+  Scripted(Vec<f64>),
+}
+
+/// Per-run clock that turns the window's real time into animation time.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+  source:    TimeSource,
+  paused:    bool,
+  step:      bool,
+  frame:     usize,
+  current:   f64,
+  last_real: Option<f64>,
+}
+
+impl Clock {
+  pub fn new(source: TimeSource) -> Self {
+    let current = match &source {
+      TimeSource::Scripted(times) => times.first().copied().unwrap_or(0.0),
+      _ => 0.0,
+    };
+
+    Self { source, current, ..Default::default() }
+  }
+
+  #[inline(always)]
+  pub fn source(&self) -> &TimeSource {
+    &self.source
+  }
+
+  /// Swap the time source, restarting the clock from its first frame.
+  pub fn set_source(&mut self, source: TimeSource) {
+    *self = Self { paused: self.paused, ..Self::new(source) };
+  }
+
+  /// Advance by one frame and return the time to render it at. The first
+  /// call always returns the start time.
+  pub fn tick(&mut self, real_time: f64) -> f64 {
+    let last_real = self.last_real.replace(real_time);
+
+    if let Some(last_real) = last_real {
+      if !self.paused || std::mem::take(&mut self.step) {
+        self.frame += 1;
+        self.current = match &self.source {
+          TimeSource::RealTime => self.current + (real_time - last_real),
+          TimeSource::FixedStep(step) => self.frame as f64 * step,
+          TimeSource::Scripted(times) => times.get(self.frame)
+                                              .or(times.last())
+                                              .copied()
+                                              .unwrap_or(0.0),
+        };
+      }
+    }
+
+    self.current
+  }
+
+  /// Time returned by the last call to `tick`.
+  #[inline(always)]
+  pub fn time(&self) -> f64 {
+    self.current
+  }
+
+  /// Number of frames the clock has advanced since it started.
+  #[inline(always)]
+  pub fn frame(&self) -> usize {
+    self.frame
+  }
+
+  #[inline(always)]
+  pub fn is_paused(&self) -> bool {
+    self.paused
+  }
+
+  pub fn set_paused(&mut self, paused: bool) {
+    self.paused = paused;
+    self.step = false;
+  }
+
+  #[inline(always)]
+  pub fn toggle_pause(&mut self) {
+    self.set_paused(!self.paused);
+  }
+
+  /// Pause the clock and let exactly one more frame through.
+  pub fn step(&mut self) {
+    self.paused = true;
+    self.step = true;
+  }
+}
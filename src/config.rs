@@ -0,0 +1,261 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::application::AppConfig;
+use crate::record::{RecordOutput, Recording};
+
+pub const USAGE: &str = "\
+Options:
+  --width <N>            Window width in pixels
+  --height <N>           Window height in pixels
+  --samples <N>          MSAA samples of the default framebuffer
+  --fullscreen[=BOOL]    Open the window fullscreen on the primary monitor
+  --vsync[=BOOL]         Wait for vertical sync when swapping buffers
+  --headless[=BOOL]      Render offscreen without opening a window
+  --frames <N>           Exit after rendering N frames
+  --media-dir <DIR>      Directory used in place of `media/`
+  --program-cache <DIR>  Cache linked program binaries in DIR
+  --record <PATH>        Record every frame, to PATH.y4m or a directory of PNGs
+  --record-fps <N>       Frame rate of the recording, 60 by default
+  --config <FILE>        Read options from FILE instead of sb7.ini / sb7.toml
+  --help                 Print this message
+
+Any boolean option can also be turned off with `--no-<option>`.
+The config file holds the same options as `key = value` lines.";
+
+/// Config files looked up in the working directory when `--config` isn't given.
+const DEFAULT_FILES: [&str; 2] = ["sb7.ini", "sb7.toml"];
+
+/// Settings layered on top of whatever `Application::init` returns. Fields
+/// left as `None` keep the app's own value.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Overrides {
+  pub width:         Option<usize>,
+  pub height:        Option<usize>,
+  pub samples:       Option<u32>,
+  pub fullscreen:    Option<bool>,
+  pub vsync:         Option<bool>,
+  pub headless:      Option<bool>,
+  pub frames:        Option<u32>,
+  pub media_dir:     Option<String>,
+  pub program_cache: Option<String>,
+  pub record:        Option<PathBuf>,
+  pub record_fps:    Option<u32>,
+  /// Config file named with `--config`.
+  pub config:        Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum ConfigErr
+{
+  IoErr(PathBuf, std::io::Error),
+  UnknownOption(String),
+  MissingValue(String),
+  BadValue(String, String),
+  SyntaxErr(PathBuf, usize),
+  HelpRequested,
+}
+
+impl Display for ConfigErr
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self
+    {
+      Self::IoErr(path, err) => write!(f, "{}: {}", path.display(), err),
+      Self::UnknownOption(key) => write!(f, "Unknown option `{}`", key),
+      Self::MissingValue(key) => write!(f, "Option `{}` needs a value", key),
+      Self::BadValue(key, value) => write!(f, "Invalid value `{}` for option `{}`", value, key),
+      Self::SyntaxErr(path, line) => write!(f, "{}:{}: expected `key = value`", path.display(), line),
+      Self::HelpRequested => write!(f, "{}", USAGE),
+    }
+  }
+}
+
+impl Error for ConfigErr
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)>
+  {
+    match self
+    {
+      Self::IoErr(_, e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+/// Every option that can be set, with `-` written as `_`.
+const KEYS: [&str; 12] = [
+  "width", "height", "samples", "fullscreen", "vsync", "headless", "frames",
+  "media_dir", "program_cache", "record", "record_fps", "config",
+];
+
+#[inline(always)]
+fn is_flag(key: &str) -> bool {
+  matches!(key, "fullscreen" | "vsync" | "headless")
+}
+
+fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, ConfigErr> {
+  value.parse().map_err(|_| ConfigErr::BadValue(key.into(), value.into()))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, ConfigErr> {
+  match value.to_ascii_lowercase().as_str() {
+    "true" | "yes" | "on" | "1" => Ok(true),
+    "false" | "no" | "off" | "0" => Ok(false),
+    _ => Err(ConfigErr::BadValue(key.into(), value.into())),
+  }
+}
+
+impl Overrides {
+  /// Command line overrides from the process arguments layered over the
+  /// config file, if there is one. Arguments that aren't options of ours are
+  /// skipped, see `from_args_lenient`.
+  pub fn load() -> Result<Self, ConfigErr> {
+    let args = Self::from_args_lenient(std::env::args().skip(1))?;
+
+    let file = match &args.config {
+      Some(path) => Self::from_file(path)?,
+      None => match DEFAULT_FILES.iter().map(Path::new).find(|p| p.is_file()) {
+        Some(path) => Self::from_file(path)?,
+        None => Self::default(),
+      },
+    };
+
+    Ok(file.merge(args))
+  }
+
+  /// Parse `--key value`, `--key=value` and `--[no-]flag` style arguments.
+  pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ConfigErr> {
+    Self::parse_args(args, true)
+  }
+
+  /// Like `from_args`, but arguments that aren't known options are skipped
+  /// with a warning, so test harness flags or the app's own arguments don't
+  /// stop it from running.
+  pub fn from_args_lenient<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ConfigErr> {
+    Self::parse_args(args, false)
+  }
+
+  fn parse_args<I: IntoIterator<Item = String>>(args: I, strict: bool) -> Result<Self, ConfigErr> {
+    let mut overrides = Self::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+      let Some(opt) = arg.strip_prefix("--") else {
+        match strict {
+          true => return Err(ConfigErr::UnknownOption(arg)),
+          false => {
+            eprintln!("Ignoring argument `{}`", arg);
+            continue;
+          }
+        }
+      };
+      let (key, value) = match opt.split_once('=') {
+        Some((key, value)) => (key.replace('-', "_"), Some(value.to_string())),
+        None => (opt.replace('-', "_"), None),
+      };
+
+      let known = key == "help" || KEYS.contains(&key.as_str()) || key.strip_prefix("no_").is_some_and(is_flag);
+      if !known && !strict {
+        eprintln!("Ignoring unknown option `{}`", arg);
+        continue;
+      }
+
+      match (key.as_str(), value) {
+        ("help", _) => return Err(ConfigErr::HelpRequested),
+        (key, None) if is_flag(key) => overrides.set(key, "true")?,
+        (key, None) if key.starts_with("no_") && is_flag(&key[3..]) => overrides.set(&key[3..], "false")?,
+        (key, Some(value)) => overrides.set(key, &value)?,
+        (key, None) => {
+          let value = args.next().ok_or_else(|| ConfigErr::MissingValue(key.into()))?;
+          overrides.set(key, &value)?
+        }
+      }
+    }
+
+    Ok(overrides)
+  }
+
+  pub fn from_file(path: &Path) -> Result<Self, ConfigErr> {
+    let src = std::fs::read_to_string(path).map_err(|e| ConfigErr::IoErr(path.into(), e))?;
+    Self::parse(&src).map_err(|e| match e {
+      ConfigErr::SyntaxErr(_, line) => ConfigErr::SyntaxErr(path.into(), line),
+      e => e,
+    })
+  }
+
+  /// Parse `key = value` lines. Blank lines, `#`/`;` comments and `[section]`
+  /// headers are skipped, so both simple INI and TOML files are accepted.
+  pub fn parse(src: &str) -> Result<Self, ConfigErr> {
+    let mut overrides = Self::default();
+
+    for (i, line) in src.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with(['#', ';']) || line.starts_with('[') {
+        continue;
+      }
+
+      let (key, value) = line.split_once('=').ok_or_else(|| ConfigErr::SyntaxErr(PathBuf::new(), i + 1))?;
+      let key = key.trim().replace('-', "_");
+      let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+      overrides.set(&key, value)?;
+    }
+
+    Ok(overrides)
+  }
+
+  fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigErr> {
+    match key {
+      "width"         => self.width         = Some(parse(key, value)?),
+      "height"        => self.height        = Some(parse(key, value)?),
+      "samples"       => self.samples       = Some(parse(key, value)?),
+      "fullscreen"    => self.fullscreen    = Some(parse_bool(key, value)?),
+      "vsync"         => self.vsync         = Some(parse_bool(key, value)?),
+      "headless"      => self.headless      = Some(parse_bool(key, value)?),
+      "frames"        => self.frames        = Some(parse(key, value)?),
+      "media_dir"     => self.media_dir     = Some(value.into()),
+      "program_cache" => self.program_cache = Some(value.into()),
+      "record"        => self.record        = Some(value.into()),
+      "record_fps"    => self.record_fps    = Some(parse::<std::num::NonZeroU32>(key, value)?.get()),
+      "config"        => self.config        = Some(value.into()),
+      _ => return Err(ConfigErr::UnknownOption(key.into())),
+    }
+    Ok(())
+  }
+
+  /// Combine two sets of overrides, values in `other` win.
+  pub fn merge(self, other: Self) -> Self {
+    Self {
+      width:         other.width.or(self.width),
+      height:        other.height.or(self.height),
+      samples:       other.samples.or(self.samples),
+      fullscreen:    other.fullscreen.or(self.fullscreen),
+      vsync:         other.vsync.or(self.vsync),
+      headless:      other.headless.or(self.headless),
+      frames:        other.frames.or(self.frames),
+      media_dir:     other.media_dir.or(self.media_dir),
+      program_cache: other.program_cache.or(self.program_cache),
+      record:        other.record.or(self.record),
+      record_fps:    other.record_fps.or(self.record_fps),
+      config:        other.config.or(self.config),
+    }
+  }
+
+  pub fn apply(&self, info: &mut AppConfig) {
+    if let Some(width) = self.width { info.width = width; }
+    if let Some(height) = self.height { info.height = height; }
+    if let Some(samples) = self.samples { info.samples = samples; }
+    if let Some(fullscreen) = self.fullscreen { info.flags.fullscreen = fullscreen; }
+    if let Some(vsync) = self.vsync { info.flags.vsync = vsync; }
+    if let Some(headless) = self.headless { info.flags.headless = headless; }
+    if let Some(frames) = self.frames { info.frames = frames; }
+    if let Some(media_dir) = &self.media_dir { info.media_dir = media_dir.clone(); }
+    if let Some(dir) = &self.program_cache { info.program_cache = Some(dir.clone()); }
+    if let Some(path) = &self.record {
+      let fps = info.record.as_ref().map_or(60, |record| record.fps);
+      info.record = Some(Recording { output: RecordOutput::from_path(path), fps });
+    }
+    if let (Some(fps), Some(record)) = (self.record_fps, &mut info.record) { record.fps = fps; }
+  }
+}
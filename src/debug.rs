@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::ffi::c_void;
+use std::fmt::Display;
+
+use gl::types::{GLchar, GLenum, GLsizei, GLuint};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  Notification,
+  Low,
+  Medium,
+  High,
+}
+
+impl Severity {
+  fn from_gl(severity: GLenum) -> Self {
+    match severity {
+      gl::DEBUG_SEVERITY_HIGH => Self::High,
+      gl::DEBUG_SEVERITY_MEDIUM => Self::Medium,
+      gl::DEBUG_SEVERITY_LOW => Self::Low,
+      _ => Self::Notification,
+    }
+  }
+}
+
+/// One message from `glDebugMessageCallback`.
+#[derive(Debug, Clone)]
+pub struct DebugMessage<'a> {
+  pub source:   GLenum,
+  pub kind:     GLenum,
+  pub id:       GLuint,
+  pub severity: Severity,
+  pub text:     &'a str,
+}
+
+impl Display for DebugMessage<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let source = match self.source {
+      gl::DEBUG_SOURCE_API => "API",
+      gl::DEBUG_SOURCE_WINDOW_SYSTEM => "WINDOW_SYSTEM",
+      gl::DEBUG_SOURCE_SHADER_COMPILER => "SHADER_COMPILER",
+      gl::DEBUG_SOURCE_THIRD_PARTY => "THIRD_PARTY",
+      gl::DEBUG_SOURCE_APPLICATION => "APPLICATION",
+      _ => "OTHER",
+    };
+    let kind = match self.kind {
+      gl::DEBUG_TYPE_ERROR => "ERROR",
+      gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "DEPRECATED_BEHAVIOR",
+      gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "UNDEFINED_BEHAVIOR",
+      gl::DEBUG_TYPE_PORTABILITY => "PORTABILITY",
+      gl::DEBUG_TYPE_PERFORMANCE => "PERFORMANCE",
+      gl::DEBUG_TYPE_MARKER => "MARKER",
+      _ => "OTHER",
+    };
+    write!(f, "[GL {:?}] {} {} {:#x}: {}", self.severity, source, kind, self.id, self.text)
+  }
+}
+
+/// Where debug messages end up when `AppFlags::debug` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct DebugOutput {
+  /// Messages less severe than this are dropped.
+  pub min_severity: Severity,
+  /// Only pass the first occurrence of each message to the sink.
+  pub dedup: bool,
+  pub sink: fn(&DebugMessage),
+}
+
+impl Default for DebugOutput {
+  fn default() -> Self {
+    Self {
+      min_severity: Severity::Low,
+      dedup: true,
+      sink: |msg| eprintln!("{}", msg),
+    }
+  }
+}
+
+/// Filters messages for `DebugOutput::sink`, receiving them while installed
+/// as the context's debug callback.
+pub struct DebugLog {
+  output:    DebugOutput,
+  seen:      HashSet<(GLenum, GLenum, GLuint, String)>,
+  /// Whether there is a callback to remove on drop.
+  installed: bool,
+}
+
+impl DebugLog {
+  pub fn new(output: DebugOutput) -> Self {
+    Self { output, seen: HashSet::new(), installed: false }
+  }
+
+  /// Enable synchronous debug output on the current context and start
+  /// forwarding it. The callback is removed again when the log is dropped.
+  pub(crate) fn install(output: DebugOutput) -> Box<Self> {
+    let mut log = Box::new(Self::new(output));
+    log.installed = true;
+    crate::gl! {
+      gl::Enable(gl::DEBUG_OUTPUT);
+      gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+      gl::DebugMessageCallback(Some(callback), &mut *log as *mut Self as *const c_void);
+    }
+    log
+  }
+
+  /// Pass `msg` on to the sink, unless it is below `min_severity` or a
+  /// repeat that `dedup` drops.
+  pub fn report(&mut self, msg: &DebugMessage) {
+    if msg.severity < self.output.min_severity {
+      return;
+    }
+    if self.output.dedup && !self.seen.insert((msg.source, msg.kind, msg.id, msg.text.to_string())) {
+      return;
+    }
+    (self.output.sink)(msg);
+  }
+}
+
+impl Drop for DebugLog {
+  fn drop(&mut self) {
+    if !self.installed {
+      return;
+    }
+    crate::gl! {
+      gl::DebugMessageCallback(None, std::ptr::null());
+      gl::Disable(gl::DEBUG_OUTPUT);
+    }
+  }
+}
+
+extern "system" fn callback(source: GLenum,
+                            kind: GLenum,
+                            id: GLuint,
+                            severity: GLenum,
+                            length: GLsizei,
+                            message: *const GLchar,
+                            user_param: *mut c_void) {
+  let text = unsafe {
+    match length {
+      0.. => String::from_utf8_lossy(std::slice::from_raw_parts(message as *const u8, length as usize)),
+      _ => std::ffi::CStr::from_ptr(message).to_string_lossy(),
+    }
+  };
+  let log = unsafe { &mut *(user_param as *mut DebugLog) };
+
+  log.report(&DebugMessage {
+    source,
+    kind,
+    id,
+    severity: Severity::from_gl(severity),
+    text: text.trim_end_matches(['\0', '\n']),
+  });
+}
+
+/// Why a robust context reported that it was lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetStatus {
+  /// This context caused the reset.
+  Guilty,
+  /// Some other context caused the reset.
+  Innocent,
+  Unknown,
+}
+
+impl ResetStatus {
+  /// Poll `glGetGraphicsResetStatus`, `None` means the context is fine.
+  pub fn poll() -> Option<Self> {
+    match crate::gl!(gl::GetGraphicsResetStatus()) {
+      gl::NO_ERROR => None,
+      gl::GUILTY_CONTEXT_RESET => Some(Self::Guilty),
+      gl::INNOCENT_CONTEXT_RESET => Some(Self::Innocent),
+      _ => Some(Self::Unknown),
+    }
+  }
+}
@@ -0,0 +1,324 @@
+use std::error::Error;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fmt::Display;
+
+#[cfg(unix)]
+extern "C" {
+  fn dlopen(filename: *const c_char, flags: i32) -> *mut c_void;
+  fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+  fn dlclose(handle: *mut c_void) -> i32;
+}
+
+const RTLD_NOW: i32 = 2;
+
+/// A shared library opened with `dlopen`, so neither EGL nor OSMesa has to be
+/// there at link time.
+struct Library(*mut c_void);
+
+impl Library {
+  #[cfg(not(unix))]
+  fn open(_names: &[&CStr]) -> Option<Self> {
+    None
+  }
+
+  #[cfg(unix)]
+  fn open(names: &[&CStr]) -> Option<Self> {
+    names.iter()
+         .map(|name| unsafe { dlopen(name.as_ptr(), RTLD_NOW) })
+         .find(|handle| !handle.is_null())
+         .map(Self)
+  }
+
+  fn get(&self, name: &CStr) -> *mut c_void {
+    #[cfg(unix)]
+    return unsafe { dlsym(self.0, name.as_ptr()) };
+    #[cfg(not(unix))]
+    return std::ptr::null_mut();
+  }
+}
+
+impl Drop for Library {
+  fn drop(&mut self) {
+    #[cfg(unix)]
+    unsafe { dlclose(self.0) };
+  }
+}
+
+/// Why no headless context could be created, one message per backend tried.
+#[derive(Debug)]
+pub struct HeadlessErr(pub Vec<String>);
+
+impl Display for HeadlessErr {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Failed to create a headless OpenGL context ({})", self.0.join("; "))
+  }
+}
+
+impl Error for HeadlessErr {}
+
+/// What the context is asked for, taken from `AppConfig`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContextHints {
+  pub(crate) version: (u32, u32),
+  pub(crate) debug:   bool,
+  pub(crate) robust:  bool,
+  pub(crate) width:   u32,
+  pub(crate) height:  u32,
+}
+
+mod egl {
+  use std::ffi::{c_char, c_void};
+
+  pub const PLATFORM_SURFACELESS_MESA: u32 = 0x31DD;
+  pub const NONE: i32 = 0x3038;
+  pub const SURFACE_TYPE: i32 = 0x3033;
+  pub const PBUFFER_BIT: i32 = 0x0001;
+  pub const RENDERABLE_TYPE: i32 = 0x3040;
+  pub const OPENGL_BIT: i32 = 0x0008;
+  pub const WIDTH: i32 = 0x3057;
+  pub const HEIGHT: i32 = 0x3056;
+  pub const OPENGL_API: u32 = 0x30A2;
+  pub const CONTEXT_MAJOR_VERSION: i32 = 0x3098;
+  pub const CONTEXT_MINOR_VERSION: i32 = 0x30FB;
+  pub const CONTEXT_OPENGL_PROFILE_MASK: i32 = 0x30FD;
+  pub const CONTEXT_OPENGL_CORE_PROFILE_BIT: i32 = 0x0001;
+  pub const CONTEXT_OPENGL_DEBUG: i32 = 0x31B0;
+  pub const CONTEXT_OPENGL_FORWARD_COMPATIBLE: i32 = 0x31B1;
+  pub const CONTEXT_OPENGL_ROBUST_ACCESS: i32 = 0x31B2;
+  pub const CONTEXT_OPENGL_RESET_NOTIFICATION_STRATEGY: i32 = 0x31BD;
+  pub const LOSE_CONTEXT_ON_RESET: i32 = 0x31BF;
+
+  pub type GetProcAddress = unsafe extern "C" fn(*const c_char) -> *mut c_void;
+  pub type GetPlatformDisplay = unsafe extern "C" fn(u32, *mut c_void, *const i32) -> *mut c_void;
+  pub type Initialize = unsafe extern "C" fn(*mut c_void, *mut i32, *mut i32) -> u32;
+  pub type BindApi = unsafe extern "C" fn(u32) -> u32;
+  pub type ChooseConfig = unsafe extern "C" fn(*mut c_void, *const i32, *mut *mut c_void, i32, *mut i32) -> u32;
+  pub type CreatePbufferSurface = unsafe extern "C" fn(*mut c_void, *mut c_void, *const i32) -> *mut c_void;
+  pub type CreateContext = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void, *const i32) -> *mut c_void;
+  pub type MakeCurrent = unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void, *mut c_void) -> u32;
+  pub type DestroyContext = unsafe extern "C" fn(*mut c_void, *mut c_void) -> u32;
+  pub type DestroySurface = unsafe extern "C" fn(*mut c_void, *mut c_void) -> u32;
+  pub type Terminate = unsafe extern "C" fn(*mut c_void) -> u32;
+}
+
+mod osmesa {
+  use std::ffi::c_void;
+
+  pub const FORMAT: i32 = 0x22;
+  pub const RGBA: i32 = 0x1908;
+  pub const DEPTH_BITS: i32 = 0x30;
+  pub const STENCIL_BITS: i32 = 0x31;
+  pub const PROFILE: i32 = 0x33;
+  pub const CORE_PROFILE: i32 = 0x34;
+  pub const CONTEXT_MAJOR_VERSION: i32 = 0x36;
+  pub const CONTEXT_MINOR_VERSION: i32 = 0x37;
+
+  pub type CreateContextAttribs = unsafe extern "C" fn(*const i32, *mut c_void) -> *mut c_void;
+  pub type MakeCurrent = unsafe extern "C" fn(*mut c_void, *mut c_void, u32, i32, i32) -> u8;
+  pub type DestroyContext = unsafe extern "C" fn(*mut c_void);
+}
+
+/// Look up `name` in `lib` as a function pointer of type `F`.
+///
+/// # Safety
+/// `F` must be the function pointer type matching the symbol's C signature.
+unsafe fn symbol<F: Copy>(lib: &Library, name: &CStr) -> Result<F, String> {
+  let ptr = lib.get(name);
+  match ptr.is_null() {
+    true => Err(format!("missing {}", name.to_string_lossy())),
+    false => Ok(std::mem::transmute_copy(&ptr)),
+  }
+}
+
+enum Backend {
+  Egl {
+    lib:     Library,
+    display: *mut c_void,
+    surface: *mut c_void,
+    context: *mut c_void,
+  },
+  OsMesa {
+    lib:     Library,
+    context: *mut c_void,
+    /// Color buffer OSMesa renders the default framebuffer into, never read
+    /// since the app draws into `Offscreen`.
+    _buffer: Vec<u8>,
+  },
+}
+
+/// An OpenGL context made current without a window or a display server,
+/// through EGL on Mesa's surfaceless platform or, failing that, OSMesa.
+pub(crate) struct HeadlessContext {
+  backend: Backend,
+}
+
+impl std::fmt::Debug for HeadlessContext {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.backend {
+      Backend::Egl { .. } => write!(f, "HeadlessContext(EGL)"),
+      Backend::OsMesa { .. } => write!(f, "HeadlessContext(OSMesa)"),
+    }
+  }
+}
+
+impl HeadlessContext {
+  pub(crate) fn new(hints: ContextHints) -> Result<Self, HeadlessErr> {
+    let mut errors = Vec::new();
+    match Self::egl(hints) {
+      Ok(context) => return Ok(context),
+      Err(err) => errors.push(format!("EGL: {}", err)),
+    }
+    match Self::osmesa(hints) {
+      Ok(context) => return Ok(context),
+      Err(err) => errors.push(format!("OSMesa: {}", err)),
+    }
+    Err(HeadlessErr(errors))
+  }
+
+  fn egl(hints: ContextHints) -> Result<Self, String> {
+    let lib = Library::open(&[c"libEGL.so.1", c"libEGL.so"]).ok_or("libEGL not found")?;
+    unsafe {
+      let get_proc_address: egl::GetProcAddress = symbol(&lib, c"eglGetProcAddress")?;
+      let initialize: egl::Initialize = symbol(&lib, c"eglInitialize")?;
+      let bind_api: egl::BindApi = symbol(&lib, c"eglBindAPI")?;
+      let choose_config: egl::ChooseConfig = symbol(&lib, c"eglChooseConfig")?;
+      let create_pbuffer_surface: egl::CreatePbufferSurface = symbol(&lib, c"eglCreatePbufferSurface")?;
+      let create_context: egl::CreateContext = symbol(&lib, c"eglCreateContext")?;
+      let make_current: egl::MakeCurrent = symbol(&lib, c"eglMakeCurrent")?;
+      let terminate: egl::Terminate = symbol(&lib, c"eglTerminate")?;
+
+      let get_platform_display = [c"eglGetPlatformDisplayEXT", c"eglGetPlatformDisplay"]
+        .iter()
+        .map(|name| get_proc_address(name.as_ptr()))
+        .find(|ptr| !ptr.is_null())
+        .ok_or("no eglGetPlatformDisplay")?;
+      let get_platform_display: egl::GetPlatformDisplay = std::mem::transmute(get_platform_display);
+
+      let display = get_platform_display(egl::PLATFORM_SURFACELESS_MESA, std::ptr::null_mut(), std::ptr::null());
+      if display.is_null() {
+        return Err("no surfaceless display, EGL_MESA_platform_surfaceless missing".into());
+      }
+      let (mut major, mut minor) = (0, 0);
+      if initialize(display, &mut major, &mut minor) == 0 {
+        return Err("eglInitialize failed".into());
+      }
+
+      let result = (|| {
+        if bind_api(egl::OPENGL_API) == 0 {
+          return Err("desktop OpenGL not supported".to_string());
+        }
+
+        let config_attribs = [egl::SURFACE_TYPE, egl::PBUFFER_BIT, egl::RENDERABLE_TYPE, egl::OPENGL_BIT, egl::NONE];
+        let (mut config, mut count) = (std::ptr::null_mut(), 0);
+        if choose_config(display, config_attribs.as_ptr(), &mut config, 1, &mut count) == 0 || count == 0 {
+          return Err("no OpenGL capable config".to_string());
+        }
+
+        let mut context_attribs = vec![
+          egl::CONTEXT_MAJOR_VERSION, hints.version.0 as i32,
+          egl::CONTEXT_MINOR_VERSION, hints.version.1 as i32,
+          egl::CONTEXT_OPENGL_PROFILE_MASK, egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+          egl::CONTEXT_OPENGL_FORWARD_COMPATIBLE, 1,
+          egl::CONTEXT_OPENGL_DEBUG, hints.debug as i32,
+        ];
+        if hints.robust {
+          context_attribs.extend([egl::CONTEXT_OPENGL_ROBUST_ACCESS, 1,
+                                  egl::CONTEXT_OPENGL_RESET_NOTIFICATION_STRATEGY, egl::LOSE_CONTEXT_ON_RESET]);
+        }
+        context_attribs.push(egl::NONE);
+        let context = create_context(display, config, std::ptr::null_mut(), context_attribs.as_ptr());
+        if context.is_null() {
+          return Err(format!("no OpenGL {}.{} core context", hints.version.0, hints.version.1));
+        }
+
+        // Surfaceless if EGL_KHR_surfaceless_context allows it, a pbuffer otherwise
+        let mut surface = std::ptr::null_mut();
+        if make_current(display, surface, surface, context) == 0 {
+          let pbuffer_attribs = [egl::WIDTH, hints.width as i32, egl::HEIGHT, hints.height as i32, egl::NONE];
+          surface = create_pbuffer_surface(display, config, pbuffer_attribs.as_ptr());
+          if surface.is_null() || make_current(display, surface, surface, context) == 0 {
+            return Err("eglMakeCurrent failed".to_string());
+          }
+        }
+        Ok((surface, context))
+      })();
+
+      match result {
+        Ok((surface, context)) => Ok(Self { backend: Backend::Egl { lib, display, surface, context } }),
+        Err(err) => {
+          terminate(display);
+          Err(err)
+        }
+      }
+    }
+  }
+
+  fn osmesa(hints: ContextHints) -> Result<Self, String> {
+    let lib = Library::open(&[c"libOSMesa.so.8", c"libOSMesa.so.6", c"libOSMesa.so"]).ok_or("libOSMesa not found")?;
+    unsafe {
+      let create_context: osmesa::CreateContextAttribs = symbol(&lib, c"OSMesaCreateContextAttribs")?;
+      let make_current: osmesa::MakeCurrent = symbol(&lib, c"OSMesaMakeCurrent")?;
+      let destroy_context: osmesa::DestroyContext = symbol(&lib, c"OSMesaDestroyContext")?;
+
+      let attribs = [
+        osmesa::FORMAT, osmesa::RGBA,
+        osmesa::DEPTH_BITS, 24,
+        osmesa::STENCIL_BITS, 8,
+        osmesa::PROFILE, osmesa::CORE_PROFILE,
+        osmesa::CONTEXT_MAJOR_VERSION, hints.version.0 as i32,
+        osmesa::CONTEXT_MINOR_VERSION, hints.version.1 as i32,
+        0,
+      ];
+      let context = create_context(attribs.as_ptr(), std::ptr::null_mut());
+      if context.is_null() {
+        return Err(format!("no OpenGL {}.{} core context", hints.version.0, hints.version.1));
+      }
+
+      let mut buffer = vec![0u8; hints.width.max(1) as usize * hints.height.max(1) as usize * 4];
+      if make_current(context, buffer.as_mut_ptr() as _, gl::UNSIGNED_BYTE, hints.width.max(1) as _, hints.height.max(1) as _) == 0 {
+        destroy_context(context);
+        return Err("OSMesaMakeCurrent failed".into());
+      }
+      Ok(Self { backend: Backend::OsMesa { lib, context, _buffer: buffer } })
+    }
+  }
+
+  pub(crate) fn get_proc_address(&self, name: &str) -> *const c_void {
+    let Ok(name) = CString::new(name) else { return std::ptr::null() };
+    let (lib, getter) = match &self.backend {
+      Backend::Egl { lib, .. } => (lib, c"eglGetProcAddress"),
+      Backend::OsMesa { lib, .. } => (lib, c"OSMesaGetProcAddress"),
+    };
+    unsafe {
+      match symbol::<egl::GetProcAddress>(lib, getter) {
+        Ok(get_proc_address) => get_proc_address(name.as_ptr()),
+        Err(_) => std::ptr::null_mut(),
+      }
+    }
+  }
+}
+
+impl Drop for HeadlessContext {
+  fn drop(&mut self) {
+    unsafe {
+      match &self.backend {
+        Backend::Egl { lib, display, surface, context } => {
+          let make_current = symbol::<egl::MakeCurrent>(lib, c"eglMakeCurrent");
+          let destroy_context = symbol::<egl::DestroyContext>(lib, c"eglDestroyContext");
+          let destroy_surface = symbol::<egl::DestroySurface>(lib, c"eglDestroySurface");
+          let terminate = symbol::<egl::Terminate>(lib, c"eglTerminate");
+          let null = std::ptr::null_mut();
+          if let Ok(make_current) = make_current { make_current(*display, null, null, null); }
+          if let Ok(destroy_context) = destroy_context { destroy_context(*display, *context); }
+          if let (Ok(destroy_surface), false) = (destroy_surface, surface.is_null()) { destroy_surface(*display, *surface); }
+          if let Ok(terminate) = terminate { terminate(*display); }
+        }
+        Backend::OsMesa { lib, context, .. } => {
+          if let Ok(destroy_context) = symbol::<osmesa::DestroyContext>(lib, c"OSMesaDestroyContext") {
+            destroy_context(*context);
+          }
+        }
+      }
+    }
+  }
+}
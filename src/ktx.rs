@@ -3,9 +3,10 @@ pub mod file
 
 use std::error::Error;
 use std::fmt::Display;
-use std::io::{ Read, Seek, SeekFrom };
+use std::collections::BTreeMap;
+use std::io::{ Read, Write };
 use std::mem::size_of;
-use std::ffi::c_void;
+use std::ops::Range;
 
 const IDENTIFIER: [u8; 12] =
   [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
@@ -13,27 +14,109 @@ const IDENTIFIER: [u8; 12] =
 #[inline(always)]
 fn swap32(n: u32) -> u32 { n.swap_bytes() }
 
-fn calculate_stride(h: &Header, width: i32, pad: i32) -> i32
+/// Whether `gltype` packs all components of a texel into one element.
+fn is_packed(gltype: u32) -> bool
 {
-  let channels: i32 = match h.glbaseinternalformat
+  matches!(gltype,
+    gl::UNSIGNED_BYTE_3_3_2 | gl::UNSIGNED_BYTE_2_3_3_REV |
+    gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_5_6_5_REV |
+    gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_4_4_4_4_REV |
+    gl::UNSIGNED_SHORT_5_5_5_1 | gl::UNSIGNED_SHORT_1_5_5_5_REV |
+    gl::UNSIGNED_INT_8_8_8_8 | gl::UNSIGNED_INT_8_8_8_8_REV |
+    gl::UNSIGNED_INT_10_10_10_2 | gl::UNSIGNED_INT_2_10_10_10_REV |
+    gl::UNSIGNED_INT_10F_11F_11F_REV | gl::UNSIGNED_INT_5_9_9_9_REV |
+    gl::UNSIGNED_INT_24_8 | gl::FLOAT_32_UNSIGNED_INT_24_8_REV)
+}
+
+/// Number of components of pixel transfer `format`, 0 if unknown.
+fn components(format: u32) -> i32
+{
+  match format
   {
-    gl::RED => 1,
-    gl::RG => 2,
-    gl::BGR | gl::RGB => 3,
-    gl::BGRA | gl::RGBA => 4,
+    gl::RED | gl::GREEN | gl::BLUE | gl::ALPHA | gl::RED_INTEGER | gl::GREEN_INTEGER | gl::BLUE_INTEGER |
+    gl::DEPTH_COMPONENT | gl::STENCIL_INDEX => 1,
+    gl::RG | gl::RG_INTEGER | gl::DEPTH_STENCIL => 2,
+    gl::BGR | gl::RGB | gl::BGR_INTEGER | gl::RGB_INTEGER => 3,
+    gl::BGRA | gl::RGBA | gl::BGRA_INTEGER | gl::RGBA_INTEGER => 4,
     _ => 0
-  };
+  }
+}
 
-  let stride = h.gltypesize as i32 * channels * width;
+/// The base internal format that pixel transfer `format` maps to.
+fn base_format(format: u32) -> u32
+{
+  match format
+  {
+    gl::RED_INTEGER => gl::RED,
+    gl::GREEN_INTEGER => gl::GREEN,
+    gl::BLUE_INTEGER => gl::BLUE,
+    gl::RG_INTEGER => gl::RG,
+    gl::BGR | gl::BGR_INTEGER | gl::RGB_INTEGER => gl::RGB,
+    gl::BGRA | gl::BGRA_INTEGER | gl::RGBA_INTEGER => gl::RGBA,
+    format => format,
+  }
+}
 
-  (stride + (pad - 1)) & !(pad - 1)
+/// The format and type that hold sized `internalformat` without conversion.
+fn transfer_format(internalformat: u32) -> Option<(u32, u32)>
+{
+  let format = match internalformat
+  {
+    gl::R8 | gl::R8_SNORM | gl::R16 | gl::R16_SNORM | gl::R16F | gl::R32F => gl::RED,
+    gl::RG8 | gl::RG8_SNORM | gl::RG16 | gl::RG16_SNORM | gl::RG16F | gl::RG32F => gl::RG,
+    gl::RGB8 | gl::RGB8_SNORM | gl::RGB16 | gl::RGB16_SNORM | gl::RGB16F | gl::RGB32F | gl::SRGB8 |
+    gl::R11F_G11F_B10F | gl::RGB9_E5 | gl::RGB565 => gl::RGB,
+    gl::RGBA8 | gl::RGBA8_SNORM | gl::RGBA16 | gl::RGBA16_SNORM | gl::RGBA16F | gl::RGBA32F | gl::SRGB8_ALPHA8 |
+    gl::RGB10_A2 | gl::RGBA4 | gl::RGB5_A1 => gl::RGBA,
+    gl::R8I | gl::R8UI | gl::R16I | gl::R16UI | gl::R32I | gl::R32UI => gl::RED_INTEGER,
+    gl::RG8I | gl::RG8UI | gl::RG16I | gl::RG16UI | gl::RG32I | gl::RG32UI => gl::RG_INTEGER,
+    gl::RGB8I | gl::RGB8UI | gl::RGB16I | gl::RGB16UI | gl::RGB32I | gl::RGB32UI => gl::RGB_INTEGER,
+    gl::RGBA8I | gl::RGBA8UI | gl::RGBA16I | gl::RGBA16UI | gl::RGBA32I | gl::RGBA32UI | gl::RGB10_A2UI => gl::RGBA_INTEGER,
+    gl::DEPTH_COMPONENT16 | gl::DEPTH_COMPONENT24 | gl::DEPTH_COMPONENT32 | gl::DEPTH_COMPONENT32F => gl::DEPTH_COMPONENT,
+    gl::DEPTH24_STENCIL8 | gl::DEPTH32F_STENCIL8 => gl::DEPTH_STENCIL,
+    gl::STENCIL_INDEX8 => gl::STENCIL_INDEX,
+    _ => return None,
+  };
+  let gltype = match internalformat
+  {
+    gl::R11F_G11F_B10F => gl::UNSIGNED_INT_10F_11F_11F_REV,
+    gl::RGB9_E5 => gl::UNSIGNED_INT_5_9_9_9_REV,
+    gl::RGB10_A2 | gl::RGB10_A2UI => gl::UNSIGNED_INT_2_10_10_10_REV,
+    gl::RGB565 => gl::UNSIGNED_SHORT_5_6_5,
+    gl::RGBA4 => gl::UNSIGNED_SHORT_4_4_4_4,
+    gl::RGB5_A1 => gl::UNSIGNED_SHORT_5_5_5_1,
+    gl::DEPTH24_STENCIL8 => gl::UNSIGNED_INT_24_8,
+    gl::DEPTH32F_STENCIL8 => gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+    gl::R8_SNORM | gl::RG8_SNORM | gl::RGB8_SNORM | gl::RGBA8_SNORM |
+    gl::R8I | gl::RG8I | gl::RGB8I | gl::RGBA8I => gl::BYTE,
+    gl::R16 | gl::RG16 | gl::RGB16 | gl::RGBA16 | gl::DEPTH_COMPONENT16 |
+    gl::R16UI | gl::RG16UI | gl::RGB16UI | gl::RGBA16UI => gl::UNSIGNED_SHORT,
+    gl::R16_SNORM | gl::RG16_SNORM | gl::RGB16_SNORM | gl::RGBA16_SNORM |
+    gl::R16I | gl::RG16I | gl::RGB16I | gl::RGBA16I => gl::SHORT,
+    gl::R16F | gl::RG16F | gl::RGB16F | gl::RGBA16F => gl::HALF_FLOAT,
+    gl::R32F | gl::RG32F | gl::RGB32F | gl::RGBA32F | gl::DEPTH_COMPONENT32F => gl::FLOAT,
+    gl::R32I | gl::RG32I | gl::RGB32I | gl::RGBA32I => gl::INT,
+    gl::R32UI | gl::RG32UI | gl::RGB32UI | gl::RGBA32UI | gl::DEPTH_COMPONENT24 | gl::DEPTH_COMPONENT32 => gl::UNSIGNED_INT,
+    _ => gl::UNSIGNED_BYTE,
+  };
+  Some((format, gltype))
 }
 
-fn calculate_face_size(h: &Header) -> i32
+fn calculate_stride(h: &Header, width: i32, pad: i32) -> i32
 {
-  let stride = calculate_stride(&h, h.pixelwidth as _, 4);
+  let channels = match is_packed(h.gltype)
+  {
+    true => 1,
+    false => match components(h.glformat)
+    {
+      0 => components(h.glbaseinternalformat),
+      n => n,
+    },
+  };
+
+  let stride = h.gltypesize as i32 * channels * width;
 
-  stride * h.pixelheight as i32
+  (stride + (pad - 1)) & !(pad - 1)
 }
 
 #[derive(Default, Debug, Clone)]
@@ -56,7 +139,135 @@ pub struct Header
   keypairbytes:             u32,
 }
 
-pub struct KtxTex(pub u32, pub Header);
+pub struct KtxTex(pub u32, pub Header, pub KeyValues);
+
+/// `KTXorientation`, which way the texel coordinates of the image increase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orientation
+{
+  /// `S=r`, otherwise `S=l`.
+  pub s_right: bool,
+  /// `T=d`, the first row is the top one. GL puts the first row at the
+  /// bottom, so such images need V flipped.
+  pub t_down:  bool,
+  /// `R=i` or `R=o`, only given for 3D textures.
+  pub r_in:    Option<bool>,
+}
+
+impl Orientation
+{
+  pub const KEY: &'static str = "KTXorientation";
+
+  /// Parse a value such as `S=r,T=d`.
+  pub fn parse(value: &str) -> Option<Self>
+  {
+    let (mut s, mut t, mut r) = (None, None, None);
+    for part in value.trim_end_matches('\0').split(',')
+    {
+      match part.trim().split_once('=')?
+      {
+        ("S", "r") => s = Some(true),
+        ("S", "l") => s = Some(false),
+        ("T", "d") => t = Some(true),
+        ("T", "u") => t = Some(false),
+        ("R", "i") => r = Some(true),
+        ("R", "o") => r = Some(false),
+        _ => return None,
+      }
+    }
+    Some(Self { s_right: s?, t_down: t?, r_in: r })
+  }
+
+  #[inline(always)]
+  pub fn flip_v(&self) -> bool { self.t_down }
+}
+
+impl Display for Orientation
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "S={},T={}", if self.s_right { 'r' } else { 'l' }, if self.t_down { 'd' } else { 'u' })?;
+    match self.r_in
+    {
+      Some(r_in) => write!(f, ",R={}", if r_in { 'i' } else { 'o' }),
+      None => Ok(()),
+    }
+  }
+}
+
+/// The key/value data of a KTX file. Keys are UTF-8, values are raw bytes,
+/// string values keep their terminating NUL as the spec recommends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyValues(pub BTreeMap<String, Vec<u8>>);
+
+impl KeyValues
+{
+  /// Parse the `bytesOfKeyValueData` section, `swap` for files of the other
+  /// endianness.
+  pub fn parse(data: &[u8], swap: bool) -> Result<Self, OpenErr>
+  {
+    let mut map = BTreeMap::new();
+    let mut offset = 0;
+    while offset + 4 <= data.len()
+    {
+      let size = u32::from_ne_bytes(data[offset..offset + 4].try_into().unwrap());
+      let size = if swap { swap32(size) } else { size } as usize;
+      offset += 4;
+
+      let pair = data.get(offset..offset + size).ok_or(OpenErr::KeyValueErr)?;
+      let nul = pair.iter().position(|&b| b == 0).ok_or(OpenErr::KeyValueErr)?;
+      let key = std::str::from_utf8(&pair[..nul]).map_err(|_| OpenErr::KeyValueErr)?;
+      map.insert(key.to_string(), pair[nul + 1..].to_vec());
+
+      // Each pair is padded to 4 bytes
+      offset += (size + 3) & !3;
+    }
+    Ok(Self(map))
+  }
+
+  #[inline(always)]
+  pub fn get(&self, key: &str) -> Option<&[u8]> { self.0.get(key).map(|value| &value[..]) }
+
+  /// A string value, without its NUL.
+  pub fn get_str(&self, key: &str) -> Option<&str>
+  {
+    let value = self.get(key)?;
+    std::str::from_utf8(value.strip_suffix(&[0]).unwrap_or(value)).ok()
+  }
+
+  /// Set a string value, NUL terminated.
+  pub fn insert_str(&mut self, key: &str, value: &str)
+  {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    self.0.insert(key.into(), bytes);
+  }
+
+  /// `KTXorientation`, `None` if it's missing or malformed.
+  pub fn orientation(&self) -> Option<Orientation>
+  {
+    self.get_str(Orientation::KEY).and_then(Orientation::parse)
+  }
+
+  pub fn set_orientation(&mut self, orientation: Orientation)
+  {
+    self.insert_str(Orientation::KEY, &orientation.to_string());
+  }
+
+  /// The `bytesOfKeyValueData` section, in native endianness.
+  pub fn to_bytes(&self) -> Vec<u8>
+  {
+    let mut bytes = Vec::new();
+    for (key, value) in &self.0
+    {
+      bytes.extend(((key.len() + 1 + value.len()) as u32).to_ne_bytes());
+      bytes.extend(key.as_bytes());
+      bytes.push(0);
+      bytes.extend(value);
+      bytes.resize((bytes.len() + 3) & !3, 0);
+    }
+    bytes
+  }
+}
 
 #[derive(Debug)]
 pub enum OpenErr
@@ -64,6 +275,14 @@ pub enum OpenErr
   IoErr(std::io::Error),
   HeaderErr,
   UnSupportedTargetErr,
+  /// A texture format whose texel size isn't known.
+  UnSupportedFormatErr,
+  /// The image data is shorter than the header says, or the header's
+  /// format can't be sized.
+  DataErr,
+  /// A key/value pair runs past the end of the key/value data, or its key
+  /// isn't a NUL terminated UTF-8 string.
+  KeyValueErr,
 }
 
 impl Display for OpenErr
@@ -73,7 +292,10 @@ impl Display for OpenErr
     {
       Self::IoErr(err) => write!(f, "IoErr: {}", err),
       Self::HeaderErr => write!(f, "Header of file doesn't match"),
-      Self::UnSupportedTargetErr => write!(f, "Unkonwn texture target type")
+      Self::UnSupportedTargetErr => write!(f, "Unkonwn texture target type"),
+      Self::UnSupportedFormatErr => write!(f, "Texture format of unknown size"),
+      Self::DataErr => write!(f, "Image data is truncated"),
+      Self::KeyValueErr => write!(f, "Malformed key/value data"),
     }
   }
 }
@@ -95,41 +317,531 @@ trait LoadHeader: std::io::Read {
   fn load_header(&mut self) -> Result<Header, OpenErr> {
     use OpenErr::*;
     let mut buf = [0u8; size_of::<Header>()];
-    self.read(&mut buf[..]).map_err(IoErr)?;
+    self.read_exact(&mut buf[..]).map_err(IoErr)?;
 
     unsafe { Ok((*(buf.as_ptr() as *const Header)).clone()) }
   }
 }
 
-impl LoadHeader for std::fs::File {}
+impl<R: std::io::Read> LoadHeader for R {}
+
+/// A parsed KTX file, kept in memory without touching GL.
+#[derive(Debug, Clone)]
+pub struct KtxImage
+{
+  pub header:     Header,
+  pub key_values: KeyValues,
+  /// `GL_TEXTURE_2D`, `GL_TEXTURE_CUBE_MAP`, ... guessed from the header.
+  pub target:     u32,
+  /// Row alignment of the pixel data, for `GL_UNPACK_ALIGNMENT`.
+  pub alignment:  u32,
+  data:           Vec<u8>,
+  /// Byte range of every image, by level, then layer, then face.
+  images:         Vec<Range<usize>>,
+}
+
+/// Byte size of one element of `gltype`, a whole texel for packed types, 0
+/// for types this writer doesn't know.
+fn type_size(gltype: u32) -> u32
+{
+  match gltype
+  {
+    gl::BYTE | gl::UNSIGNED_BYTE | gl::UNSIGNED_BYTE_3_3_2 | gl::UNSIGNED_BYTE_2_3_3_REV => 1,
+    gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT |
+    gl::UNSIGNED_SHORT_5_6_5 | gl::UNSIGNED_SHORT_5_6_5_REV |
+    gl::UNSIGNED_SHORT_4_4_4_4 | gl::UNSIGNED_SHORT_4_4_4_4_REV |
+    gl::UNSIGNED_SHORT_5_5_5_1 | gl::UNSIGNED_SHORT_1_5_5_5_REV => 2,
+    gl::INT | gl::UNSIGNED_INT | gl::FLOAT |
+    gl::UNSIGNED_INT_8_8_8_8 | gl::UNSIGNED_INT_8_8_8_8_REV |
+    gl::UNSIGNED_INT_10_10_10_2 | gl::UNSIGNED_INT_2_10_10_10_REV |
+    gl::UNSIGNED_INT_10F_11F_11F_REV | gl::UNSIGNED_INT_5_9_9_9_REV | gl::UNSIGNED_INT_24_8 => 4,
+    gl::FLOAT_32_UNSIGNED_INT_24_8_REV => 8,
+    _ => 0,
+  }
+}
+
+impl KtxImage
+{
+  /// A zeroed image to be filled through `image_mut`, with rows 4 byte
+  /// aligned. `extent` gives the width, height and depth, 0 for unused
+  /// dimensions, `layers` is 0 for non-array textures. Cube maps get six
+  /// faces. Only for uncompressed formats, images of formats that can't be
+  /// sized are empty.
+  pub fn new(target: u32, glinternalformat: u32, glformat: u32, gltype: u32, extent: [u32; 3], layers: u32, levels: u32) -> Self
+  {
+    let header = Self::make_header(target, glinternalformat, glformat, gltype, extent, layers, levels.max(1));
+    let images = header.arrayelements.max(1) * header.faces.max(1);
+    let data = (0..header.miplevels).map(|level| vec![0; image_size(&header, level, 4) * images as usize]).collect();
+    Self::from_levels(header, target, data)
+  }
+
+  fn make_header(target: u32, glinternalformat: u32, glformat: u32, gltype: u32, [width, height, depth]: [u32; 3], layers: u32, levels: u32) -> Header
+  {
+    let cube = target == gl::TEXTURE_CUBE_MAP || target == gl::TEXTURE_CUBE_MAP_ARRAY;
+    Header {
+      identifier:           IDENTIFIER,
+      endianness:           0x04030201,
+      gltype,
+      gltypesize:           if gltype == gl::NONE { 1 } else { type_size(gltype) },
+      glformat,
+      glinternalformat,
+      glbaseinternalformat: base_format(glformat),
+      pixelwidth:           width,
+      pixelheight:          height,
+      pixeldepth:           depth,
+      arrayelements:        layers,
+      faces:                if cube { 6 } else { 1 },
+      miplevels:            levels,
+      keypairbytes:         0,
+    }
+  }
+
+  /// Split every level evenly into its layers and faces.
+  fn from_levels(header: Header, target: u32, levels: Vec<Vec<u8>>) -> Self
+  {
+    let per_level = (header.arrayelements.max(1) * header.faces.max(1)) as usize;
+    let mut data = Vec::new();
+    let mut images = Vec::new();
+    for level in levels
+    {
+      let size = level.len() / per_level;
+      for i in 0..per_level
+      {
+        images.push(data.len() + i * size..data.len() + (i + 1) * size);
+      }
+      data.extend(level);
+    }
+    Self { header, key_values: KeyValues::default(), target, alignment: 4, data, images }
+  }
+
+  #[inline(always)]
+  pub fn levels(&self) -> u32 { self.header.miplevels }
+
+  #[inline(always)]
+  pub fn layers(&self) -> u32 { self.header.arrayelements.max(1) }
+
+  #[inline(always)]
+  pub fn faces(&self) -> u32 { self.header.faces.max(1) }
+
+  #[inline(always)]
+  pub fn is_compressed(&self) -> bool { self.header.gltype == gl::NONE }
+
+  /// Width, height and depth of mip `level`, unused dimensions stay 0.
+  pub fn extent(&self, level: u32) -> [u32; 3]
+  {
+    let h = &self.header;
+    let size = |n: u32| if n == 0 { 0 } else { (n >> level).max(1) };
+    [size(h.pixelwidth), size(h.pixelheight), size(h.pixeldepth)]
+  }
+
+  /// One face of one array layer of mip `level`, with all of its depth
+  /// slices.
+  pub fn image(&self, level: u32, layer: u32, face: u32) -> &[u8]
+  {
+    let index = (level * self.layers() + layer) * self.faces() + face;
+    &self.data[self.images[index as usize].clone()]
+  }
+
+  pub fn image_mut(&mut self, level: u32, layer: u32, face: u32) -> &mut [u8]
+  {
+    let index = (level * self.layers() + layer) * self.faces() + face;
+    &mut self.data[self.images[index as usize].clone()]
+  }
+
+  /// `image` with rows padded to 4 bytes as KTX 1.1 wants them.
+  fn aligned_image(&self, level: u32, layer: u32, face: u32) -> std::borrow::Cow<'_, [u8]>
+  {
+    let image = self.image(level, layer, face);
+    if self.alignment == 4 || self.is_compressed()
+    {
+      return image.into();
+    }
+
+    let h = &self.header;
+    let [w, height, depth] = self.extent(level);
+    let (src, dst) = (calculate_stride(h, w as _, self.alignment as _) as usize, calculate_stride(h, w as _, 4) as usize);
+    let row = calculate_stride(h, w as _, 1) as usize;
+    let mut aligned = vec![0; dst * (height.max(1) * depth.max(1)) as usize];
+    for (from, to) in image.chunks(src).zip(aligned.chunks_mut(dst))
+    {
+      to[..row].copy_from_slice(&from[..row]);
+    }
+    aligned.into()
+  }
+
+  /// Write a KTX 1.1 file with `imageSize`s, padding and the key/value
+  /// data, in native endianness.
+  pub fn write<W: Write>(&self, mut writer: W) -> std::io::Result<()>
+  {
+    let key_values = self.key_values.to_bytes();
+    let mut h = self.header.clone();
+    h.identifier = IDENTIFIER;
+    h.endianness = 0x04030201;
+    h.keypairbytes = key_values.len() as u32;
+
+    let header = unsafe { std::slice::from_raw_parts(&h as *const Header as *const u8, size_of::<Header>()) };
+    writer.write_all(header)?;
+    writer.write_all(&key_values)?;
+
+    let plain_cube = h.faces == 6 && h.arrayelements == 0;
+    let padding = |n: usize| &[0u8; 3][..(4 - n % 4) % 4];
+    for level in 0..self.levels()
+    {
+      let images: Vec<_> = (0..self.layers())
+        .flat_map(|layer| (0..self.faces()).map(move |face| (layer, face)))
+        .map(|(layer, face)| self.aligned_image(level, layer, face))
+        .collect();
+
+      // The size of one face for cube maps, of the whole level otherwise
+      let image_size = match plain_cube
+      {
+        true => images[0].len(),
+        false => images.iter().map(|image| image.len()).sum(),
+      };
+      writer.write_all(&(image_size as u32).to_ne_bytes())?;
+      for image in &images
+      {
+        writer.write_all(image)?;
+        if plain_cube
+        {
+          writer.write_all(padding(image.len()))?;
+        }
+      }
+      if !plain_cube
+      {
+        writer.write_all(padding(image_size))?;
+      }
+    }
+    Ok(())
+  }
+
+  /// `write` to `filename`.
+  pub fn save(&self, filename: &str) -> std::io::Result<()>
+  {
+    let file = std::fs::File::create(filename)?;
+    let mut writer = std::io::BufWriter::new(file);
+    self.write(&mut writer)?;
+    writer.flush()
+  }
+
+  /// Read every level of texture `tex` back with `glGetTextureImage`, in
+  /// the format and type matching its internal format, or those GL prefers
+  /// for unsized ones. Compressed textures are read as they are. Fails for
+  /// targets KTX can't hold, and for formats whose size isn't known.
+  pub fn from_texture(tex: u32) -> Result<Self, OpenErr>
+  {
+    use crate::gl;
+    let level_param = |level: u32, pname: u32| {
+      let mut value = 0;
+      gl!(gl::GetTextureLevelParameteriv(tex, level as _, pname, &mut value));
+      value as u32
+    };
+
+    let mut target = 0;
+    gl!(gl::GetTextureParameteriv(tex, gl::TEXTURE_TARGET, &mut target));
+    let target = target as u32;
+
+    let [w, h, d] = [gl::TEXTURE_WIDTH, gl::TEXTURE_HEIGHT, gl::TEXTURE_DEPTH].map(|pname| level_param(0, pname));
+    let (extent, layers) = match target
+    {
+      gl::TEXTURE_1D => ([w, 0, 0], 0),
+      gl::TEXTURE_1D_ARRAY => ([w, 0, 0], h),
+      gl::TEXTURE_2D | gl::TEXTURE_CUBE_MAP => ([w, h, 0], 0),
+      gl::TEXTURE_2D_ARRAY => ([w, h, 0], d),
+      gl::TEXTURE_CUBE_MAP_ARRAY => ([w, h, 0], d / 6),
+      gl::TEXTURE_3D => ([w, h, d], 0),
+      _ => return Err(OpenErr::UnSupportedTargetErr),
+    };
+
+    let mut levels = 0;
+    gl!(gl::GetTextureParameteriv(tex, gl::TEXTURE_IMMUTABLE_LEVELS, &mut levels));
+    let levels = match levels
+    {
+      0 => (0..32).take_while(|&level| level_param(level, gl::TEXTURE_WIDTH) != 0).count() as u32,
+      n => n as u32,
+    };
+
+    let internal = level_param(0, gl::TEXTURE_INTERNAL_FORMAT);
+    let compressed = level_param(0, gl::TEXTURE_COMPRESSED) != 0;
+
+    let (format, gltype) = transfer_format(internal).unwrap_or_else(|| {
+      let (mut format, mut gltype) = (0, 0);
+      gl!(gl::GetInternalformativ(target, internal, gl::TEXTURE_IMAGE_FORMAT, 1, &mut format));
+      gl!(gl::GetInternalformativ(target, internal, gl::TEXTURE_IMAGE_TYPE, 1, &mut gltype));
+      (format as u32, gltype as u32)
+    });
+
+    let mut header = match compressed
+    {
+      true => Self::make_header(target, internal, 0, 0, extent, layers, levels),
+      false => Self::make_header(target, internal, format, gltype, extent, layers, levels),
+    };
+    header.glbaseinternalformat = base_format(format);
+    if !compressed && image_size(&header, 0, 4) == 0
+    {
+      return Err(OpenErr::UnSupportedFormatErr);
+    }
+
+    let (mut alignment, mut pack_buffer) = (0, 0);
+    gl!(gl::GetIntegerv(gl::PACK_ALIGNMENT, &mut alignment));
+    gl!(gl::GetIntegerv(gl::PIXEL_PACK_BUFFER_BINDING, &mut pack_buffer));
+    gl!(gl::PixelStorei(gl::PACK_ALIGNMENT, 4));
+    gl!(gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0));
+
+    let images = (header.arrayelements.max(1) * header.faces.max(1)) as usize;
+    let data = (0..levels).map(|level| {
+      match compressed
+      {
+        true => {
+          // The size of cube maps is that of one face
+          let faces = if target == gl::TEXTURE_CUBE_MAP { 6 } else { 1 };
+          let mut data = vec![0u8; level_param(level, gl::TEXTURE_COMPRESSED_IMAGE_SIZE) as usize * faces];
+          gl!(gl::GetCompressedTextureImage(tex, level as _, data.len() as _, data.as_mut_ptr() as _));
+          data
+        }
+        false => {
+          let mut data = vec![0u8; image_size(&header, level, 4) * images];
+          gl!(gl::GetTextureImage(tex, level as _, header.glformat, header.gltype, data.len() as _, data.as_mut_ptr() as _));
+          data
+        }
+      }
+    }).collect();
+
+    gl!(gl::PixelStorei(gl::PACK_ALIGNMENT, alignment));
+    gl!(gl::BindBuffer(gl::PIXEL_PACK_BUFFER, pack_buffer as _));
 
-struct Void(*const c_void);
+    Ok(Self::from_levels(header, target, data))
+  }
+
+  /// Every layer and face of mip `level`, one after another.
+  pub fn level(&self, level: u32) -> &[u8]
+  {
+    let per_level = (self.layers() * self.faces()) as usize;
+    let images = &self.images[level as usize * per_level..(level as usize + 1) * per_level];
+    &self.data[images[0].start..images[per_level - 1].end]
+  }
 
-impl std::ops::Add<i32> for Void {
-  type Output = *const c_void;
+  /// The pixel data as it is in the file, following the key/value data.
   #[inline(always)]
-  fn add(self, rhs: i32) -> Self::Output {
-    unsafe { (self.0).add(rhs as usize) }
+  pub fn data(&self) -> &[u8] { &self.data }
+
+  /// Create the storage of `tex`, or of a new texture if it's 0, and upload
+  /// every level to it. Returns the texture.
+  pub fn upload(&self, tex: u32) -> Result<u32, OpenErr>
+  {
+    use crate::gl;
+    let h = &self.header;
+    let target = self.target;
+    let levels = h.miplevels as i32;
+    let layers = self.layers() as i32;
+
+    match target
+    {
+      gl::TEXTURE_1D | gl::TEXTURE_1D_ARRAY | gl::TEXTURE_2D | gl::TEXTURE_2D_ARRAY |
+      gl::TEXTURE_CUBE_MAP | gl::TEXTURE_CUBE_MAP_ARRAY | gl::TEXTURE_3D => {}
+      _ => return Err(OpenErr::UnSupportedTargetErr),
+    }
+
+    let mut tex = tex;
+    if tex == 0
+    {
+      gl!(gl::GenTextures(1, &mut tex));
+    }
+    gl!(gl::BindTexture(target, tex));
+
+    let mut alignment = 0;
+    gl!(gl::GetIntegerv(gl::UNPACK_ALIGNMENT, &mut alignment));
+    gl!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, self.alignment as _));
+
+    // 2D images of a level, compressed or not
+    let sub_image_2d = |target: u32, level: i32, [w, h_, _]: [u32; 3], data: &[u8]| match self.is_compressed()
+    {
+      true => gl!(gl::CompressedTexSubImage2D(target, level, 0, 0, w as _, h_ as _, h.glinternalformat, data.len() as _, data.as_ptr() as _)),
+      false => gl!(gl::TexSubImage2D(target, level, 0, 0, w as _, h_ as _, h.glformat, h.gltype, data.as_ptr() as _)),
+    };
+    let sub_image_3d = |level: i32, [w, h_, _]: [u32; 3], depth: i32, data: &[u8]| match self.is_compressed()
+    {
+      true => gl!(gl::CompressedTexSubImage3D(target, level, 0, 0, 0, w as _, h_ as _, depth, h.glinternalformat, data.len() as _, data.as_ptr() as _)),
+      false => gl!(gl::TexSubImage3D(target, level, 0, 0, 0, w as _, h_ as _, depth, h.glformat, h.gltype, data.as_ptr() as _)),
+    };
+
+    match target
+    {
+      gl::TEXTURE_1D => gl!(gl::TexStorage1D(target, levels, h.glinternalformat, h.pixelwidth as _)),
+      gl::TEXTURE_2D | gl::TEXTURE_CUBE_MAP =>
+        gl!(gl::TexStorage2D(target, levels, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _)),
+      gl::TEXTURE_1D_ARRAY => gl!(gl::TexStorage2D(target, levels, h.glinternalformat, h.pixelwidth as _, layers)),
+      gl::TEXTURE_3D =>
+        gl!(gl::TexStorage3D(target, levels, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, h.pixeldepth as _)),
+      gl::TEXTURE_2D_ARRAY =>
+        gl!(gl::TexStorage3D(target, levels, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, layers)),
+      gl::TEXTURE_CUBE_MAP_ARRAY =>
+        gl!(gl::TexStorage3D(target, levels, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, layers * 6)),
+      _ => {}
+    }
+
+    for level in 0..h.miplevels
+    {
+      let extent = self.extent(level);
+      let data = self.level(level);
+      let l = level as i32;
+      match target
+      {
+        gl::TEXTURE_1D =>
+          gl!(gl::TexSubImage1D(target, l, 0, extent[0] as _, h.glformat, h.gltype, data.as_ptr() as _)),
+        gl::TEXTURE_1D_ARRAY => sub_image_2d(target, l, [extent[0], layers as _, 0], data),
+        gl::TEXTURE_2D => sub_image_2d(target, l, extent, data),
+        gl::TEXTURE_CUBE_MAP => for face in 0..self.faces()
+        {
+          sub_image_2d(gl::TEXTURE_CUBE_MAP_POSITIVE_X + face, l, extent, self.image(level, 0, face));
+        },
+        gl::TEXTURE_3D => sub_image_3d(l, extent, extent[2] as _, data),
+        gl::TEXTURE_2D_ARRAY => sub_image_3d(l, extent, layers, data),
+        gl::TEXTURE_CUBE_MAP_ARRAY => sub_image_3d(l, extent, layers * 6, data),
+        _ => {}
+      }
+    }
+
+    gl!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, alignment));
+
+    if h.miplevels == 1
+    {
+      gl!(gl::GenerateMipmap(target));
+    }
+
+    Ok(tex)
   }
 }
 
-pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
+/// Whether the largest image of an uncompressed `h` is small enough for the
+/// `i32` arithmetic of `calculate_stride` and `image_size`, assuming at most
+/// 4 channels and rows padded to 4 bytes.
+fn fits_i32(h: &Header) -> bool
 {
-  use OpenErr::*;
+  if h.gltype == gl::NONE
+  {
+    return true;
+  }
+  let stride = (h.gltypesize as u64 * 4).checked_mul(h.pixelwidth as u64).map(|n| n + 3);
+  let size = stride
+    .and_then(|n| n.checked_mul(h.pixelheight.max(1) as u64))
+    .and_then(|n| n.checked_mul(h.pixeldepth.max(1) as u64));
+  size.is_some_and(|n| n <= i32::MAX as u64)
+}
+
+/// Byte size of one image of mip `level`, with rows padded to `pad`.
+fn image_size(h: &Header, level: u32, pad: i32) -> usize
+{
+  let size = |n: u32| (n >> level).max(1) as i32;
+  (calculate_stride(h, size(h.pixelwidth), pad) * size(h.pixelheight) * size(h.pixeldepth)) as usize
+}
+
+/// Bytes GL reads for one image of mip `level` with rows padded to `pad`,
+/// which doesn't include the padding of the last row.
+fn unpack_size(h: &Header, level: u32, pad: i32) -> usize
+{
+  let size = |n: u32| (n >> level).max(1) as usize;
+  let rows = size(h.pixelheight) * size(h.pixeldepth);
+  let width = size(h.pixelwidth) as i32;
+  calculate_stride(h, width, pad) as usize * (rows - 1) + calculate_stride(h, width, 1) as usize
+}
+
+/// Images laid out as KTX 1.1 says: every level starts with its
+/// `imageSize`, faces of plain cube maps and levels are padded to 4 bytes.
+/// `None` unless the sizes add up to exactly `data`.
+fn spec_layout(h: &Header, data: &[u8], swap: bool) -> Option<Vec<Range<usize>>>
+{
+  let images = (h.arrayelements.max(1) * h.faces.max(1)) as usize;
+  let plain_cube = h.faces == 6 && h.arrayelements == 0;
+  let pad4 = |n: usize| (n + 3) & !3;
+
+  let mut ranges = Vec::new();
+  let mut offset = 0;
+  for _ in 0..h.miplevels
+  {
+    let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().unwrap();
+    let image_size = u32::from_ne_bytes(bytes);
+    let image_size = if swap { swap32(image_size) } else { image_size } as usize;
+    offset += 4;
+
+    let size = match plain_cube
+    {
+      true => image_size,
+      false if image_size.is_multiple_of(images) => image_size / images,
+      false => return None,
+    };
+    if size == 0
+    {
+      return None;
+    }
+    for _ in 0..images
+    {
+      ranges.push(offset..offset + size);
+      offset += if plain_cube { pad4(size) } else { size };
+      if offset > data.len()
+      {
+        return None;
+      }
+    }
+    offset = pad4(offset);
+  }
+
+  (offset == pad4(data.len())).then_some(ranges)
+}
+
+/// Images packed back to back without `imageSize`s, as the SuperBible's own
+/// tools write them, with rows padded to `pad`.
+fn packed_layout(h: &Header, data: &[u8], pad: i32) -> Option<Vec<Range<usize>>>
+{
+  let images = h.arrayelements.max(1) * h.faces.max(1);
+
+  if h.gltype == gl::NONE
+  {
+    // The size of compressed images can't be known without imageSize
+    let all = 0..data.len();
+    return (h.miplevels == 1 && images == 1).then(|| vec![all]);
+  }
+
+  let mut ranges = Vec::new();
+  let mut offset = 0;
+  for level in 0..h.miplevels
+  {
+    // Formats `calculate_stride` doesn't know can't be sized
+    let size = image_size(h, level, pad);
+    if size == 0
+    {
+      return None;
+    }
+    for _ in 0..images
+    {
+      ranges.push(offset..offset + size);
+      offset += size;
+      if offset > data.len()
+      {
+        return None;
+      }
+    }
+  }
+  Some(ranges)
+}
 
-  let mut file = std::fs::File::open(filename).map_err(IoErr)?;
+/// Parse a KTX file without uploading it anywhere. Reads `reader` to the
+/// end.
+pub fn parse<R: Read>(mut reader: R) -> Result<KtxImage, OpenErr>
+{
+  use OpenErr::*;
 
-  let mut h = file.load_header()?;
+  let mut h = reader.load_header()?;
 
   if h.identifier != IDENTIFIER
   {
     return Err(HeaderErr);
   }
 
-  match h.endianness
+  let swap = match h.endianness
   {
     // No swap needed
-    0x04030201 => {}
+    0x04030201 => false,
 
     // Swap needed
     0x01020304 => {
@@ -146,6 +858,7 @@ pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
       h.faces                = swap32(h.faces);
       h.miplevels            = swap32(h.miplevels);
       h.keypairbytes         = swap32(h.keypairbytes);
+      true
     }
     _ => return Err(OpenErr::HeaderErr),
   };
@@ -166,7 +879,7 @@ pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
   {
     if h.arrayelements == 0
     {
-      if h.faces == 0
+      if h.faces != 6
       {
         gl::TEXTURE_2D
       }
@@ -177,7 +890,7 @@ pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
     }
     else
     {
-      if h.faces == 0
+      if h.faces != 6
       {
         gl::TEXTURE_2D_ARRAY
       }
@@ -200,107 +913,75 @@ pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
     return Err(OpenErr::HeaderErr);
   }
 
-  let data_start = file.stream_position().map_err(IoErr)? + h.keypairbytes as u64;
-  let data_end = file.seek(SeekFrom::End(0)).map_err(IoErr)?;
-  file.seek(SeekFrom::Start(data_start)).map_err(IoErr)?;
-
-  let len = (data_end - data_start) as usize;
-  let mut data: Vec<u8> = Vec::with_capacity(len);
-  data.resize(len, 0);
+  // A cube map has 6 faces, everything else 1 (or 0)
+  if !matches!(h.faces, 0 | 1 | 6)
+  {
+    return Err(OpenErr::HeaderErr);
+  }
 
-  file.read(&mut data).map_err(IoErr)?;
+  // Each level halves the largest dimension until it is 1
+  let max_levels = 32 - h.pixelwidth.max(h.pixelheight).max(h.pixeldepth).leading_zeros();
+  if h.miplevels > max_levels
+  {
+    return Err(OpenErr::HeaderErr);
+  }
 
-  if h.miplevels == 0
+  // The images of a level are counted in a `u32`, and strides are `i32`s
+  if h.arrayelements.max(1).checked_mul(h.faces.max(1)).is_none() || !fits_i32(&h)
   {
-    h.miplevels = 1;
+    return Err(OpenErr::HeaderErr);
   }
 
-  use crate::gl;
-  let mut tex = tex;
-  if tex == 0
+  // Don't trust `keypairbytes` with the size of an allocation
+  let mut key_values = Vec::new();
+  reader.by_ref().take(h.keypairbytes as u64).read_to_end(&mut key_values).map_err(IoErr)?;
+  if key_values.len() != h.keypairbytes as usize
   {
-    gl! { gl::GenTextures(1, &mut tex); }
+    return Err(IoErr(std::io::ErrorKind::UnexpectedEof.into()));
   }
+  let key_values = KeyValues::parse(&key_values, swap)?;
 
-  gl! { gl::BindTexture(target, tex); }
-  
-  let data = data[..].as_ptr() as *const c_void;
+  let mut data = Vec::new();
+  reader.read_to_end(&mut data).map_err(IoErr)?;
 
-  match target
+  if h.miplevels == 0
   {
-    gl::TEXTURE_1D =>
-    gl!{
-      gl::TexStorage1D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _);
-      gl::TexSubImage1D(target, 0, 0, h.pixelwidth as _, h.glformat, h.glinternalformat, data);
-    },
-    gl::TEXTURE_2D =>
-    {
-      if h.gltype == gl::NONE
-      {
-        gl!(gl::CompressedTexImage2D(target, 0, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, 0, 420 * 380 / 2, data));
-      }
-      else
-      {
-        gl!(gl::TexStorage2D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _));
-        {
-          let mut ptr = data;
-          let mut height = h.pixelheight as i32;
-          let mut width = h.pixelwidth as i32;
-          gl!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1));
-          for i in 0..h.miplevels
-          {
-              gl!(gl::TexSubImage2D(gl::TEXTURE_2D, i as _, 0, 0, width, height, h.glformat, h.gltype, ptr));
-              ptr = Void(ptr) + height * calculate_stride(&h, width, 1);
-              height >>= 1;
-              width >>= 1;
-              if height < 1 { height = 1; }
-              if width < 1  { width  = 1; }
-          }
-        }
-      }
-    },
-    gl::TEXTURE_3D =>
-    gl!{
-      gl::TexStorage3D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, h.pixeldepth as _);
-      gl::TexSubImage3D(target, 0, 0, 0, 0, h.pixelwidth as _, h.pixelheight as _, h.pixeldepth as _, h.glformat, h.glinternalformat, data);
-    },
-    gl::TEXTURE_1D_ARRAY =>
-    gl!{
-      gl::TexStorage2D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.arrayelements as _);
-      gl::TexSubImage2D(target, 0, 0, 0, h.pixelwidth as _, h.arrayelements as _, h.glformat, h.gltype, data);
-    },
-    gl::TEXTURE_2D_ARRAY =>
-    gl!{
-      gl::TexStorage3D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, h.arrayelements as _);
-      gl::TexSubImage3D(target, 0, 0, 0, 0, h.pixelwidth as _, h.pixelheight as _, h.arrayelements as _, h.glformat, h.gltype, data);
-    },
-    gl::TEXTURE_CUBE_MAP =>
+    h.miplevels = 1;
+  }
+
+  // Rows are 4 byte aligned in the spec, but the SuperBible's mipmapped
+  // textures are tightly packed
+  let exact = |pad| packed_layout(&h, &data, pad).filter(|images| images.last().map(|r| r.end) == Some(data.len()));
+  let (images, alignment) = spec_layout(&h, &data, swap).map(|images| (images, 4))
+    .or_else(|| exact(4).map(|images| (images, 4)))
+    .or_else(|| exact(1).map(|images| (images, 1)))
+    .or_else(|| packed_layout(&h, &data, 4).map(|images| (images, 4)))
+    .ok_or(DataErr)?;
+
+  // `upload` reads as many bytes as the format and extent call for, GL
+  // doesn't read the padding of the last row
+  if h.gltype != gl::NONE
+  {
+    let images_per_level = (h.arrayelements.max(1) * h.faces.max(1)) as usize;
+    for (i, image) in images.iter().enumerate()
     {
-      gl!(gl::TexStorage2D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _));
-      // glTexSubImage3D(GL_TEXTURE_CUBE_MAP, 0, 0, 0, 0, h.pixelwidth, h.pixelheight, h.faces, h.glformat, h.gltype, data);
+      let size = unpack_size(&h, (i / images_per_level) as u32, alignment as i32);
+      if size == 0 || image.len() < size
       {
-        let face_size = calculate_face_size(&h);
-        for i in 0..h.faces
-        {
-          let data = Void(data) + face_size * i as i32;
-          gl!(gl::TexSubImage2D(gl::TEXTURE_CUBE_MAP_POSITIVE_X + i, 0, 0, 0, h.pixelwidth as _, h.pixelheight as _, h.glformat, h.gltype, data as _));
-        }
+        return Err(DataErr);
       }
-    },
-    gl::TEXTURE_CUBE_MAP_ARRAY =>
-    gl!{
-      gl::TexStorage3D(target, h.miplevels as _, h.glinternalformat, h.pixelwidth as _, h.pixelheight as _, h.arrayelements as _);
-      gl::TexSubImage3D(target, 0, 0, 0, 0, h.pixelwidth as _, h.pixelheight as _, (h.faces * h.arrayelements) as _, h.glformat, h.gltype, data);
-    },
-    _ => return Err(UnSupportedTargetErr)
+    }
   }
 
-  if h.miplevels == 1
-  {
-    gl!(gl::GenerateMipmap(target));
-  }
+  Ok(KtxImage { header: h, key_values, target, alignment, data, images })
+}
 
-  Ok(KtxTex(tex, h))
+pub fn load_with_tex(filename: &str, tex: u32) -> Result<KtxTex, OpenErr>
+{
+  let file = std::fs::File::open(filename).map_err(OpenErr::IoErr)?;
+  let image = parse(std::io::BufReader::new(file))?;
+  let tex = image.upload(tex)?;
+  Ok(KtxTex(tex, image.header, image.key_values))
 }
 
 #[inline(always)]
@@ -308,4 +989,11 @@ pub fn load(filename: &str) -> Result<KtxTex, OpenErr> {
   load_with_tex(filename, 0)
 }
 
+/// Save texture `tex` with all of its levels, see `KtxImage::from_texture`.
+pub fn save(filename: &str, tex: u32) -> Result<(), OpenErr> {
+  KtxImage::from_texture(tex)?.save(filename).map_err(OpenErr::IoErr)
+}
+
 }
+
+pub use file::{parse, KtxImage};
@@ -1,10 +1,21 @@
 pub mod application;
+pub mod clock;
+pub mod config;
+pub mod debug;
+pub mod headless;
+pub mod record;
+pub mod screenshot;
+pub mod stats;
 pub mod ktx;
 pub mod object;
 pub mod vmath;
 pub mod color;
 
 mod prog;
+pub use prog::glsl;
+pub use prog::include;
+pub use prog::pipeline;
+pub use prog::reflect;
 pub use prog::program;
 pub use prog::shader;
 
@@ -17,6 +28,7 @@ macro_rules! gl {
 
 pub mod prelude {
   pub use crate::application::*;
+  pub use crate::clock::*;
   pub use crate::gl;
   pub use crate::ktx;
   pub use crate::program;
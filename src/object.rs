@@ -19,7 +19,7 @@
 // FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use std::{io::Read, mem::size_of, ptr::null};
+use std::{error::Error, fmt::Display, io::Read, mem::size_of, ptr::null};
 
 pub mod sb6m {
   #[inline(always)]
@@ -29,7 +29,7 @@ pub mod sb6m {
 
   #[inline(always)]
   pub fn magic() -> u32 {
-    fourcc(b"SM6M")
+    fourcc(b"SB6M")
   }
 
   #[allow(non_snake_case)]
@@ -135,6 +135,38 @@ pub mod sb6m {
   }
 }
 
+#[derive(Debug)]
+pub enum LoadErr
+{
+  IoErr(std::io::Error),
+  HeaderErr,
+  ChunkErr,
+}
+
+impl Display for LoadErr
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self
+    {
+      Self::IoErr(err) => write!(f, "IoErr: {}", err),
+      Self::HeaderErr => write!(f, "Not an SB6M model file"),
+      Self::ChunkErr => write!(f, "Model data is truncated or incomplete"),
+    }
+  }
+}
+
+impl Error for LoadErr
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)>
+  {
+    match self
+    {
+      Self::IoErr(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
 #[derive(Default)]
 pub struct Object {
   data_buf:        u32,
@@ -206,17 +238,31 @@ impl Object {
     self.vao
   }
 
+  /// Load an `.sbm` model, panicking if it can't be read. See `try_load`.
   pub fn load(&mut self, filename: &str) {
+    if let Err(err) = self.try_load(filename) {
+      panic!("{}: {}", filename, err);
+    }
+  }
+
+  /// Load an `.sbm` model from `filename` as given, see
+  /// `AppContext::media_path` to follow `AppConfig::media_dir`.
+  pub fn try_load(&mut self, filename: &str) -> Result<(), LoadErr> {
     self.free();
 
     let mut data = Vec::new();
-    std::fs::File::open(filename).unwrap()
-                                 .read_to_end(&mut data)
-                                 .unwrap();
+    std::fs::File::open(filename).and_then(|mut file| file.read_to_end(&mut data))
+                                                                  .map_err(LoadErr::IoErr)?;
 
     let mut offset = 0;
 
-    let header: &sb6m::Header = data.load(offset).unwrap();
+    if data.len() <= size_of::<sb6m::Header>() {
+      return Err(LoadErr::HeaderErr);
+    }
+    let header: &sb6m::Header = data.load(offset).ok_or(LoadErr::HeaderErr)?;
+    if header.magic != sb6m::magic() {
+      return Err(LoadErr::HeaderErr);
+    }
     offset += header.size as usize;
 
     let mut vertex_attrib_chunk: Option<&sb6m::VertexAttribChunk>  = None;
@@ -227,41 +273,73 @@ impl Object {
 
     use sb6m::ChunkType::*;
     for _ in 0..header.num_chunks {
-      let chunk: &sb6m::ChunkHeader = data.load(offset).unwrap();
+      let chunk: &sb6m::ChunkHeader = data.load(offset).ok_or(LoadErr::ChunkErr)?;
+      if (chunk.size as usize) < size_of::<sb6m::ChunkHeader>() || offset + chunk.size as usize > data.len() {
+        return Err(LoadErr::ChunkErr);
+      }
       let chunk_offset = offset;
       offset += chunk.size as usize;
 
       match chunk.chunk_type {
-        VERTEX_ATTRIBS  => vertex_attrib_chunk = data.load(chunk_offset),
-        VERTEX_DATA     => vertex_data_chunk   = data.load(chunk_offset),
-        INDEX_DATA      => index_data_chunk    = data.load(chunk_offset),
-        SUB_OBJECT_LIST => sub_object_chunk    = data.load(chunk_offset),
-        DATA            => data_chunk          = data.load(chunk_offset),
+        VERTEX_ATTRIBS  => vertex_attrib_chunk = Some(data.load(chunk_offset).ok_or(LoadErr::ChunkErr)?),
+        VERTEX_DATA     => vertex_data_chunk   = Some(data.load(chunk_offset).ok_or(LoadErr::ChunkErr)?),
+        INDEX_DATA      => index_data_chunk    = Some(data.load(chunk_offset).ok_or(LoadErr::ChunkErr)?),
+        SUB_OBJECT_LIST => sub_object_chunk    = Some(data.load(chunk_offset).ok_or(LoadErr::ChunkErr)?),
+        DATA            => data_chunk          = Some(data.load(chunk_offset).ok_or(LoadErr::ChunkErr)?),
         _ => {}
       }
     }
 
+    // Check every range read below against the file before creating anything
+    let index_size = |chunk: &sb6m::ChunkIndexData| {
+      chunk.index_count as usize * match chunk.index_type { gl::UNSIGNED_SHORT => size_of::<u16>() , _ => size_of::<u8>() }
+    };
+    let data_ptr = match data_chunk {
+      Some(chunk) => Some(data.chunk_at(chunk, chunk.data_offset, chunk.data_length).ok_or(LoadErr::ChunkErr)?),
+      None => None,
+    };
+    let vertex_ptr = match vertex_data_chunk {
+      Some(chunk) => Some(data.at(chunk.data_offset, chunk.data_size as _).ok_or(LoadErr::ChunkErr)?),
+      None => None,
+    };
+    let index_ptr = match index_data_chunk {
+      Some(chunk) => Some(data.at(chunk.index_data_offset, index_size(chunk)).ok_or(LoadErr::ChunkErr)?),
+      None => None,
+    };
+    let attribs = match vertex_attrib_chunk {
+      Some(chunk) => (0..chunk.attrib_count as usize).map(|i| data.load_decl(&chunk.attrib_data, i))
+                                                     .collect::<Option<Vec<_>>>()
+                                                     .ok_or(LoadErr::ChunkErr)?,
+      None => Vec::new(),
+    };
+    let sub_objects = match sub_object_chunk {
+      Some(chunk) => (0..chunk.count as usize).map(|i| data.load_decl(&chunk.sub_object, i))
+                                              .collect::<Option<Vec<_>>>()
+                                              .ok_or(LoadErr::ChunkErr)?,
+      None => Vec::new(),
+    };
+
     crate::gl! {
       gl::GenVertexArrays(1, &mut self.vao);
       gl::BindVertexArray(self.vao);
     }
 
-    if let Some(chunk) = data_chunk {
+    if let (Some(chunk), Some(ptr)) = (data_chunk, data_ptr) {
       crate::gl!{
         gl::GenBuffers(1, &mut self.data_buf);
         gl::BindBuffer(gl::ARRAY_BUFFER, self.data_buf);
-        gl::BufferData(gl::ARRAY_BUFFER, chunk.data_length as _, data.chunk_at(chunk, chunk.data_offset, chunk.data_length), gl::STATIC_DRAW);  
+        gl::BufferData(gl::ARRAY_BUFFER, chunk.data_length as _, ptr, gl::STATIC_DRAW);  
       }
     } else {
       let mut data_size = 0;
       let mut size_used = 0;
 
       if let Some(chunk) = vertex_data_chunk {
-        data_size += chunk.data_size;
+        data_size += chunk.data_size as usize;
       }
 
       if let Some(chunk) = index_data_chunk {
-        data_size += chunk.index_count * match chunk.index_type { gl::UNSIGNED_SHORT => size_of::<u16>() , _ => size_of::<u8>() } as u32;
+        data_size += index_size(chunk);
       }
 
       crate::gl! {
@@ -270,33 +348,29 @@ impl Object {
         gl::BufferData(gl::ARRAY_BUFFER, data_size as _, null(), gl::STATIC_DRAW);
       }
 
-      if let Some(chunk) = vertex_data_chunk {
-        crate::gl!(gl::BufferSubData(gl::ARRAY_BUFFER, 0, chunk.data_size as _, data.at(chunk.data_offset as _) as _));
+      if let (Some(chunk), Some(ptr)) = (vertex_data_chunk, vertex_ptr) {
+        crate::gl!(gl::BufferSubData(gl::ARRAY_BUFFER, 0, chunk.data_size as _, ptr));
         size_used += chunk.data_offset;
       }
 
-      if let Some(chunk) = index_data_chunk {
+      if let (Some(chunk), Some(ptr)) = (index_data_chunk, index_ptr) {
         crate::gl!(gl::BufferSubData(gl::ARRAY_BUFFER,
                           size_used as _,
-                          (chunk.index_count as usize
-                            * match chunk.index_type { gl::UNSIGNED_SHORT => size_of::<u16>() , _ => size_of::<u8>()}) as _,
-                          data.at(chunk.index_data_offset)));
+                          index_size(chunk) as _,
+                          ptr));
       }
     }
 
-    if let Some(chunk) = vertex_attrib_chunk {
-      for i in 0..chunk.attrib_count as usize {
-        let decl = data.load_decl(&chunk.attrib_data, i);
-        crate::gl!{
-          gl::VertexAttribPointer(i as _,
-                                  decl.size as _,
-                                  decl.data_type,
-                                  match decl.flags & sb6m::VERTEX_ATTRIB_FLAG_NORMALIZED { 0 => gl::FALSE, _ => gl::TRUE },
-                                  decl.stride as _,
-                                  decl.data_offset as _);
-          gl::EnableVertexAttribArray(i as _);
-        }
-      }  
+    for (i, decl) in attribs.iter().enumerate() {
+      crate::gl!{
+        gl::VertexAttribPointer(i as _,
+                                decl.size as _,
+                                decl.data_type,
+                                match decl.flags & sb6m::VERTEX_ATTRIB_FLAG_NORMALIZED { 0 => gl::FALSE, _ => gl::TRUE },
+                                decl.stride as _,
+                                decl.data_offset as _);
+        gl::EnableVertexAttribArray(i as _);
+      }
     }
 
     if let Some(chunk) = index_data_chunk.as_ref() {
@@ -308,15 +382,16 @@ impl Object {
     }
 
     if let Some(chunk) = sub_object_chunk {
-      for i in 0..chunk.count as usize {
-        self.sub_object.push(data.load_decl(&chunk.sub_object, i));
-      }
-
+      self.sub_object = sub_objects;
       self.num_sub_objects = chunk.count;
     } else {
       let decl = sb6m::SubObjectDecl {
         first: 0,
-        count: match self.index_type { gl::NONE => vertex_data_chunk.unwrap().total_vertices, _ => index_data_chunk.unwrap().index_count }
+        count: match (self.index_type, vertex_data_chunk, index_data_chunk) {
+          (gl::NONE, Some(chunk), _) => chunk.total_vertices,
+          (_, _, Some(chunk)) => chunk.index_count,
+          _ => return Err(LoadErr::ChunkErr),
+        }
       };
       self.sub_object.push(decl);
       self.num_sub_objects = 1;
@@ -326,12 +401,17 @@ impl Object {
       gl::BindVertexArray(0);
       gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);  
     }
+
+    Ok(())
   }
 
   pub fn free(&mut self) {
-    crate::gl! {
-      gl::DeleteVertexArrays(1, &self.vao);
-      gl::DeleteBuffers(1, &self.data_buf);
+    // Nothing to delete before the first successful load
+    if self.vao != 0 {
+      crate::gl! {
+        gl::DeleteVertexArrays(1, &self.vao);
+        gl::DeleteBuffers(1, &self.data_buf);
+      }
     }
 
     self.vao = 0;
@@ -343,8 +423,10 @@ impl Object {
 trait LoadChunk<T: Clone>: Check + std::ops::Deref<Target = [u8]> {
   #[inline(always)]
   fn load(&self, offset: usize) -> Option<&T> {
-    let end = offset + size_of::<T>();
-    assert!(end < self.len());
+    let end = offset.checked_add(size_of::<T>())?;
+    if end > self.len() {
+      return None;
+    }
 
     unsafe {
       Some(&*(&self[offset] as *const _ as *const T))
@@ -352,44 +434,43 @@ trait LoadChunk<T: Clone>: Check + std::ops::Deref<Target = [u8]> {
   }
 
   #[inline(always)]
-  fn load_decl(&self, decl: &[T; 1], index: usize) -> T {
+  fn load_decl(&self, decl: &[T; 1], index: usize) -> Option<T> {
+    let head = (decl as *const T).wrapping_add(index);
+    let tail = head.wrapping_add(1);
+    if !self.contains((head as _, tail as _)) {
+      return None;
+    }
+
     unsafe {
-      let head = (decl as *const T).add(index);
-      let tail = head.add(1);
-      self.assert_contain((head as _, tail as _));
-      (*head).clone()
+      Some((*head).clone())
     }
   }
 
   #[inline(always)]
-  fn chunk_at(&self, chunk: &T, offset: u32, data_len: u32) -> *const std::ffi::c_void {
-    unsafe {
-      let head = (chunk as *const T as *const u8).add(offset as _);
-      let tail = head.add(data_len as _);
-      self.assert_contain((head, tail));
-      head as _
-    }
+  fn chunk_at(&self, chunk: &T, offset: u32, data_len: u32) -> Option<*const std::ffi::c_void> {
+    let head = (chunk as *const T as *const u8).wrapping_add(offset as _);
+    let tail = head.wrapping_add(data_len as _);
+    self.contains((head, tail)).then_some(head as _)
   }
 }
 
 trait Check: std::ops::Deref<Target = [u8]> {
   #[inline(always)]
-  fn assert_contain(&self, range: (*const u8, *const u8)) {
-    let bounds = (self.first().unwrap() as *const u8, self.last().unwrap() as *const u8);
+  fn contains(&self, range: (*const u8, *const u8)) -> bool {
+    let bounds = self.as_ptr_range();
 
-    assert!(range.0.le(&range.1));
-    assert!((range.0 as *const u8).ge(&bounds.0));
-    assert!((range.1 as *const u8).le(&bounds.1));
+    range.0 <= range.1 && range.0 >= bounds.start && range.1 <= bounds.end
   }
 }
 
 trait Ptr: std::ops::Deref<Target = [u8]> {
   #[inline(always)]
-  fn at(&self, offset: u32) -> *const std::ffi::c_void {
-    (&self[offset as usize]) as *const _ as _
+  fn at(&self, offset: u32, len: usize) -> Option<*const std::ffi::c_void> {
+    let end = (offset as usize).checked_add(len)?;
+    (end <= self.len()).then(|| self.as_ptr().wrapping_add(offset as _) as _)
   }
 }
 
 impl<T: Clone> LoadChunk<T> for Vec<u8> {}
 impl Ptr for Vec<u8> {}
-impl Check for Vec<u8> {}
\ No newline at end of file
+impl Check for Vec<u8> {}
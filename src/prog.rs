@@ -1,41 +1,82 @@
 use std::{ffi::CString, fmt::Display};
 
+pub mod builder;
+pub mod cache;
+pub mod diagnostic;
+pub mod glsl;
+pub mod include;
+pub mod pipeline;
+pub mod reflect;
+pub(crate) mod reload;
+pub mod uniform;
+pub mod variants;
+
+use crate::application::Assets;
+use diagnostic::{Diagnostic, ShaderError, Stage};
+use include::{Preprocessor, Source};
+
 enum Obj {
   Shader(u32),
   Program(u32),
 }
 
 impl Obj {
-  fn check_errors(self) {
+  fn succeeded(&self) -> bool {
+    let mut success = gl::FALSE as _;
     super::gl! {
-      use std::ptr::null_mut;
-      use Obj::{Program, Shader};
-      let mut success = gl::FALSE as _;
       match self {
-        Shader(s) => gl::GetShaderiv(s, gl::COMPILE_STATUS, &mut success),
-        Program(p) => gl::GetProgramiv(p, gl::LINK_STATUS, &mut success),
+        Obj::Shader(s) => gl::GetShaderiv(*s, gl::COMPILE_STATUS, &mut success),
+        Obj::Program(p) => gl::GetProgramiv(*p, gl::LINK_STATUS, &mut success),
       }
+    }
+    success == gl::TRUE as _
+  }
 
-      if success == gl::TRUE as _ {
-        return;
+  /// The whole info log, drivers don't promise it to be valid UTF-8.
+  fn info_log(&self) -> String {
+    let mut len = 0;
+    super::gl! {
+      match self {
+        Obj::Shader(s) => gl::GetShaderiv(*s, gl::INFO_LOG_LENGTH, &mut len),
+        Obj::Program(p) => gl::GetProgramiv(*p, gl::INFO_LOG_LENGTH, &mut len),
       }
+    }
 
-      let mut info = [0u8; 1024];
-      let infoptr = info.as_mut_ptr() as _;
+    let mut info = vec![0u8; len.max(1) as usize];
+    let mut written = 0;
+    let infoptr = info.as_mut_ptr() as _;
+    super::gl! {
       match self {
-        Shader(shader) => gl::GetShaderInfoLog(shader, 1024, null_mut(), infoptr),
-        Program(program) => gl::GetProgramInfoLog(program, 1024, null_mut(), infoptr),
+        Obj::Shader(shader) => gl::GetShaderInfoLog(*shader, len, &mut written, infoptr),
+        Obj::Program(program) => gl::GetProgramInfoLog(*program, len, &mut written, infoptr),
       }
+    }
+    info.truncate(written.max(0) as usize);
+    String::from_utf8_lossy(&info).into_owned()
+  }
 
-      println!("======== {} ========", self);
-      use std::str::from_utf8 as from;
-      let lines = from(&info).unwrap_or("Invaild utf-8 log")
-                             .lines()
-                             .map(|s| s.trim_matches('\0'))
-                             .filter(|s| s.len() != 0);
-      for i in lines {
-        println!("== {}", i);
+  fn diagnostics(&self, source: &Source) -> Vec<Diagnostic> {
+    let stage = match self {
+      Obj::Shader(s) => {
+        let mut shader_type = 0;
+        super::gl!(gl::GetShaderiv(*s, gl::SHADER_TYPE, &mut shader_type));
+        Stage::from_gl(shader_type as u32)
       }
+      Obj::Program(_) => None,
+    };
+    Diagnostic::parse_log(&self.info_log(), stage, source)
+  }
+
+  /// Print the info log if compiling or linking failed, with locations
+  /// mapped back to the files `source` was expanded from.
+  fn check_errors(self, source: &Source) {
+    if self.succeeded() {
+      return;
+    }
+
+    println!("======== {} ========", self);
+    for diagnostic in self.diagnostics(source) {
+      println!("== {}", diagnostic);
     }
   }
 }
@@ -68,20 +109,124 @@ impl Display for Obj {
 pub mod shader {
   use super::*;
 
+  pub use super::diagnostic::{Diagnostic, Severity, ShaderError, Stage};
+
+  /// A compiled shader object, deleted when dropped.
+  #[derive(Debug)]
+  pub struct Shader {
+    id:     u32,
+    stage:  Stage,
+    source: Source,
+  }
+
+  impl Shader {
+    #[inline(always)]
+    pub fn id(&self) -> u32 {
+      self.id
+    }
+
+    #[inline(always)]
+    pub fn stage(&self) -> Stage {
+      self.stage
+    }
+
+    /// The files the shader was built from, empty for `try_from_str`.
+    #[inline(always)]
+    pub fn files(&self) -> &[std::path::PathBuf] {
+      &self.source.files
+    }
+
+    /// The source as it was compiled, after preprocessing.
+    #[inline(always)]
+    pub fn source(&self) -> &Source {
+      &self.source
+    }
+
+    /// Warnings and other messages the compiler left in the log.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+      Obj::Shader(self.id).diagnostics(&self.source)
+    }
+
+    /// Give up ownership of the GL object without deleting it.
+    pub fn into_raw(self) -> u32 {
+      let id = self.id;
+      std::mem::forget(self);
+      id
+    }
+  }
+
+  impl Drop for Shader {
+    fn drop(&mut self) {
+      crate::gl!(gl::DeleteShader(self.id));
+    }
+  }
+
+  /// Like `load`, but returns the compiler's diagnostics on failure.
+  pub fn try_load(filename: &str, stage: Stage) -> Result<Shader, ShaderError> {
+    try_compile(preprocess(&Assets::default(), filename)?, stage)
+  }
+
+  /// `try_load` with `defines` inserted after the `#version` line, so one
+  /// file can be compiled into several variants.
+  ///
+  /// ```ignore
+  /// let fs = shader::try_load_with_defines("media/shaders/hdrbloom/hdrbloom-scene.fs.glsl",
+  ///                                        Stage::Fragment,
+  ///                                        &[("BLOOM", ""), ("SAMPLES", "4")])?;
+  /// ```
+  pub fn try_load_with_defines(filename: &str, stage: Stage, defines: &[(&str, &str)]) -> Result<Shader, ShaderError> {
+    let mut source = preprocess(&Assets::default(), filename)?;
+    source.define(defines);
+    try_compile(source, stage)
+  }
+
+  pub(crate) fn preprocess(assets: &Assets, filename: &str) -> Result<Source, ShaderError> {
+    let preprocessor = Preprocessor::new(assets.include_dirs.clone());
+    preprocessor.load(&assets.path(filename)).map_err(ShaderError::SourceErr)
+  }
+
+  pub fn try_from_str(source: &str, stage: Stage) -> Result<Shader, ShaderError> {
+    try_compile(Source { text: source.into(), files: Vec::new() }, stage)
+  }
+
+  pub(crate) fn try_compile(source: Source, stage: Stage) -> Result<Shader, ShaderError> {
+    let shader = Shader { id: compile(&source, stage.to_gl(), false), stage, source };
+    match Obj::Shader(shader.id).succeeded() {
+      true => Ok(shader),
+      false => Err(ShaderError::CompileErr(stage, shader.diagnostics())),
+    }
+  }
+
+  /// Load a shader with its `#include`s expanded, looking them up in the
+  /// include directories of `Assets::default()`.
   pub fn load(filename: &str, shader_type: u32, check_errors: bool) -> u32 {
-    let str = std::fs::read_to_string(filename).unwrap_or("".into());
-    from_str(&str, shader_type, check_errors)
+    let assets = Assets::default();
+    let preprocessor = Preprocessor::new(assets.include_dirs.clone());
+    let source = match preprocessor.load(&assets.path(filename)) {
+      Ok(source) => source,
+      Err(err) => {
+        if check_errors {
+          println!("======== {} ========\n== {}", filename, err);
+        }
+        Source::default()
+      }
+    };
+    compile(&source, shader_type, check_errors)
   }
 
   pub fn from_str(source: &str, shader_type: u32, check_errors: bool) -> u32 {
+    compile(&Source { text: source.into(), files: Vec::new() }, shader_type, check_errors)
+  }
+
+  fn compile(source: &Source, shader_type: u32, check_errors: bool) -> u32 {
     crate::gl! {
       let shader = gl::CreateShader(shader_type);
-      let source = CString::new(source).unwrap();
-      gl::ShaderSource(shader, 1, &source.as_ptr(), std::ptr::null());
+      let text = CString::new(source.text.as_str()).unwrap();
+      gl::ShaderSource(shader, 1, &text.as_ptr(), std::ptr::null());
       gl::CompileShader(shader);
 
       if check_errors {
-        Obj::Shader(shader).check_errors();
+        Obj::Shader(shader).check_errors(source);
       }
 
       return shader;
@@ -91,6 +236,135 @@ pub mod shader {
 
 pub mod program {
   use super::*;
+  use super::builder::LinkOptions;
+  use super::shader::Shader;
+
+  pub use super::builder::{Builder, FeedbackMode};
+  pub use super::cache::{self, ProgramCache};
+  pub use super::uniform::{Program, Uniform};
+  pub use super::variants::Variants;
+
+  /// Load, compile and link one shader file per stage.
+  pub fn try_load(stages: &[(&str, Stage)]) -> Result<u32, ShaderError> {
+    build(&[], stages, &[], &LinkOptions::default(), &Assets::default()).map(|(program, _)| program)
+  }
+
+  /// `try_load` linked with `PROGRAM_SEPARABLE`, for use in a
+  /// `pipeline::Pipeline`.
+  pub fn try_load_separable(stages: &[(&str, Stage)]) -> Result<u32, ShaderError> {
+    let options = LinkOptions { separable: true, ..Default::default() };
+    build(&[], stages, &[], &options, &Assets::default()).map(|(program, _)| program)
+  }
+
+  /// `try_load` with `defines` inserted into every stage, see
+  /// `shader::try_load_with_defines`. Each set of defines gets its own
+  /// entry in the program cache.
+  pub fn try_load_with_defines(stages: &[(&str, Stage)], defines: &[(&str, &str)]) -> Result<u32, ShaderError> {
+    build(&[], stages, defines, &LinkOptions::default(), &Assets::default()).map(|(program, _)| program)
+  }
+
+  /// The `<dir>/<name>.<stage>.glsl` files that exist, in pipeline order.
+  pub fn find_stages(dir: &str, name: &str) -> Vec<(String, Stage)> {
+    find_stages_in(&Assets::default(), dir, name)
+  }
+
+  /// `find_stages` with `media/...` resolved through `assets`.
+  pub(crate) fn find_stages_in(assets: &Assets, dir: &str, name: &str) -> Vec<(String, Stage)> {
+    Stage::ALL.into_iter()
+              .map(|stage| (format!("{}/{}.{}.glsl", dir.trim_end_matches('/'), name, stage.suffix()), stage))
+              .filter(|(filename, _)| assets.path(filename).is_file())
+              .collect()
+  }
+
+  /// Load every stage of `name` found in `dir`, following the media pack's
+  /// `name.vs.glsl`, `name.fs.glsl`, ... naming. Returns the program and the
+  /// stages it was built from.
+  pub fn load_dir(dir: &str, name: &str) -> Result<(u32, Vec<Stage>), ShaderError> {
+    let stages = find_stages(dir, name);
+    if stages.is_empty() {
+      return Err(ShaderError::NoStagesErr(Assets::default().path(dir), name.into()));
+    }
+
+    let files: Vec<_> = stages.iter().map(|(filename, stage)| (filename.as_str(), *stage)).collect();
+    Ok((try_load(&files)?, stages.into_iter().map(|(_, stage)| stage).collect()))
+  }
+
+  /// Link `shaders` and the shaders in `files` with `options`, also
+  /// returning every file read. `defines` are inserted into `files` only.
+  /// With a program cache in `assets` the files are only compiled on a miss,
+  /// the key covers the defines since they are part of the source.
+  pub(crate) fn build(shaders: &[Shader],
+                      files: &[(&str, Stage)],
+                      defines: &[(&str, &str)],
+                      options: &LinkOptions,
+                      assets: &Assets) -> Result<(u32, Vec<std::path::PathBuf>), ShaderError> {
+    let sources = files.iter()
+                       .map(|&(filename, _)| {
+                         let mut source = shader::preprocess(assets, filename)?;
+                         source.define(defines);
+                         Ok(source)
+                       })
+                       .collect::<Result<Vec<_>, ShaderError>>()?;
+    let mut read: Vec<_> = sources.iter().flat_map(|source| source.files.iter().cloned()).collect();
+    read.sort();
+    read.dedup();
+
+    let cache = assets.program_cache.clone().map(|cache| {
+      let mut texts: Vec<_> = shaders.iter().map(|shader| (shader.stage(), shader.source().text.as_str())).collect();
+      texts.extend(files.iter().zip(&sources).map(|(&(_, stage), source)| (stage, source.text.as_str())));
+      let mut driver = cache::driver().to_vec();
+      driver.push(format!("{:?}", options));
+      let key = cache::key(&texts, &driver);
+      (cache, key)
+    });
+    if let Some(program) = cache.as_ref().and_then(|(cache, key)| cache.load_with(*key, |program| options.apply_binary(program))) {
+      return Ok((program, read));
+    }
+
+    let compiled = files.iter()
+                        .zip(sources)
+                        .map(|(&(_, stage), source)| shader::try_compile(source, stage))
+                        .collect::<Result<Vec<_>, _>>()?;
+    let all: Vec<_> = shaders.iter().chain(&compiled).collect();
+    Ok((link(&all, options, cache)?, read))
+  }
+
+  /// Link `shaders` into a new program, returning the linker's diagnostics
+  /// on failure. The shaders can be dropped once this returns.
+  pub fn try_link(shaders: &[Shader]) -> Result<u32, ShaderError> {
+    build(shaders, &[], &[], &LinkOptions::default(), &Assets::default()).map(|(program, _)| program)
+  }
+
+  fn link(shaders: &[&Shader], options: &LinkOptions, cache: Option<(ProgramCache, u64)>) -> Result<u32, ShaderError> {
+    crate::gl! {
+      let program = gl::CreateProgram();
+      options.apply(program);
+      if cache.is_some() {
+        gl::ProgramParameteri(program, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE as _);
+      }
+      for shader in shaders {
+        gl::AttachShader(program, shader.id());
+      }
+      gl::LinkProgram(program);
+      for shader in shaders {
+        gl::DetachShader(program, shader.id());
+      }
+
+      let obj = Obj::Program(program);
+      if obj.succeeded() {
+        if let Some((cache, key)) = cache {
+          if let Err(err) = cache.store(key, program) {
+            eprintln!("Failed to cache program binary: {}", err);
+          }
+        }
+        return Ok(program);
+      }
+
+      let diagnostics = obj.diagnostics(&Source::default());
+      gl::DeleteProgram(program);
+      Err(ShaderError::LinkErr(diagnostics))
+    }
+  }
 
   pub fn link_from_shaders(shaders: &[u32], delete_shaders: bool) -> u32 {
     crate::gl! {
@@ -102,7 +376,7 @@ pub mod program {
         }
       }
       gl::LinkProgram(program);
-      Obj::Program(program).check_errors();
+      Obj::Program(program).check_errors(&Source::default());
       program
     }
   }
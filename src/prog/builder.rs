@@ -0,0 +1,167 @@
+use std::ffi::CString;
+use std::path::PathBuf;
+
+use super::diagnostic::{ShaderError, Stage};
+use crate::application::Assets;
+use super::program;
+use super::shader::Shader;
+
+/// How transform feedback varyings are written to buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeedbackMode {
+  /// All varyings to one buffer.
+  #[default]
+  Interleaved,
+  /// Each varying to its own binding point.
+  Separate,
+}
+
+/// Program state that has to be set before linking.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LinkOptions {
+  pub(crate) varyings:    Vec<String>,
+  pub(crate) mode:        FeedbackMode,
+  pub(crate) attribs:     Vec<(u32, String)>,
+  pub(crate) frag_data:   Vec<(u32, u32, String)>,
+  pub(crate) retrievable: bool,
+  pub(crate) separable:   bool,
+}
+
+impl LinkOptions {
+  /// State that a program binary doesn't carry, and so has to be set again
+  /// before `glProgramBinary`.
+  pub(crate) fn apply_binary(&self, program: u32) {
+    if self.separable {
+      crate::gl!(gl::ProgramParameteri(program, gl::PROGRAM_SEPARABLE, gl::TRUE as _));
+    }
+  }
+
+  pub(crate) fn apply(&self, program: u32) {
+    self.apply_binary(program);
+    if self.retrievable {
+      crate::gl!(gl::ProgramParameteri(program, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE as _));
+    }
+
+    if !self.varyings.is_empty() {
+      let varyings: Vec<_> = self.varyings.iter().map(|name| CString::new(name.as_str()).unwrap()).collect();
+      let ptrs: Vec<_> = varyings.iter().map(|name| name.as_ptr()).collect();
+      let mode = match self.mode {
+        FeedbackMode::Interleaved => gl::INTERLEAVED_ATTRIBS,
+        FeedbackMode::Separate => gl::SEPARATE_ATTRIBS,
+      };
+      crate::gl!(gl::TransformFeedbackVaryings(program, ptrs.len() as _, ptrs.as_ptr(), mode));
+    }
+
+    for (index, name) in &self.attribs {
+      let name = CString::new(name.as_str()).unwrap();
+      crate::gl!(gl::BindAttribLocation(program, *index, name.as_ptr()));
+    }
+    for (color, index, name) in &self.frag_data {
+      let name = CString::new(name.as_str()).unwrap();
+      crate::gl!(gl::BindFragDataLocationIndexed(program, *color, *index, name.as_ptr()));
+    }
+  }
+}
+
+/// Collects shaders and pre-link state, then links them in one go.
+///
+/// ```ignore
+/// let program = program::Builder::new()
+///   .file("media/shaders/springmass/update.vs.glsl", Stage::Vertex)
+///   .transform_feedback(&["tf_position_mass", "tf_velocity"], FeedbackMode::Separate)
+///   .build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct Builder {
+  files:   Vec<(String, Stage)>,
+  shaders: Vec<Shader>,
+  defines: Vec<(String, String)>,
+  options: LinkOptions,
+  assets:  Assets,
+}
+
+impl Builder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Resolve files and includes, and cache the program, as `assets` says.
+  /// Set it before `dir`, which looks for files right away.
+  pub fn assets(mut self, assets: &Assets) -> Self {
+    self.assets = assets.clone();
+    self
+  }
+
+  /// Load a shader from a file when building.
+  pub fn file(mut self, filename: &str, stage: Stage) -> Self {
+    self.files.push((filename.into(), stage));
+    self
+  }
+
+  /// Every stage of `name` in `dir`, see `program::load_dir`.
+  pub fn dir(mut self, dir: &str, name: &str) -> Self {
+    self.files.extend(program::find_stages_in(&self.assets, dir, name));
+    self
+  }
+
+  /// Attach an already compiled shader.
+  pub fn shader(mut self, shader: Shader) -> Self {
+    self.shaders.push(shader);
+    self
+  }
+
+  /// `#define name value` in every file, see `shader::try_load_with_defines`.
+  /// Shaders added with `shader` are already compiled and don't get it.
+  pub fn define(mut self, name: &str, value: &str) -> Self {
+    self.defines.push((name.into(), value.into()));
+    self
+  }
+
+  /// Capture `varyings` with transform feedback.
+  pub fn transform_feedback(mut self, varyings: &[&str], mode: FeedbackMode) -> Self {
+    self.options.varyings = varyings.iter().map(|name| name.to_string()).collect();
+    self.options.mode = mode;
+    self
+  }
+
+  /// `glBindAttribLocation`, explicit `layout(location)`s take precedence.
+  pub fn attrib_location(mut self, index: u32, name: &str) -> Self {
+    self.options.attribs.push((index, name.into()));
+    self
+  }
+
+  /// `glBindFragDataLocation`, explicit `layout(location)`s take precedence.
+  pub fn frag_data_location(self, color: u32, name: &str) -> Self {
+    self.frag_data_location_indexed(color, 0, name)
+  }
+
+  /// `glBindFragDataLocationIndexed`, for dual source blending.
+  pub fn frag_data_location_indexed(mut self, color: u32, index: u32, name: &str) -> Self {
+    self.options.frag_data.push((color, index, name.into()));
+    self
+  }
+
+  /// Set `PROGRAM_BINARY_RETRIEVABLE_HINT`, so `glGetProgramBinary` works.
+  pub fn retrievable_binary(mut self, retrievable: bool) -> Self {
+    self.options.retrievable = retrievable;
+    self
+  }
+
+  /// Set `PROGRAM_SEPARABLE`, so the program can be used in a pipeline.
+  pub fn separable(mut self, separable: bool) -> Self {
+    self.options.separable = separable;
+    self
+  }
+
+  pub fn build(self) -> Result<u32, ShaderError> {
+    self.build_with_files().map(|(program, _)| program)
+  }
+
+  /// Build a new program from the same recipe, also returning every file
+  /// read including the `#include`s. The builder can be built again later.
+  pub(crate) fn build_with_files(&self) -> Result<(u32, Vec<PathBuf>), ShaderError> {
+    let files: Vec<_> = self.files.iter().map(|(filename, stage)| (filename.as_str(), *stage)).collect();
+    let defines: Vec<_> = self.defines.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+    program::build(&self.shaders, &files, &defines, &self.options, &self.assets)
+  }
+}
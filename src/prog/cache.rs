@@ -0,0 +1,127 @@
+use std::ffi::CStr;
+use std::hash::Hasher;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::diagnostic::Stage;
+
+/// 64-bit FNV-1a, unlike `DefaultHasher` it gives the same keys across
+/// Rust releases so the cache survives a toolchain update.
+struct Fnv(u64);
+
+impl Default for Fnv {
+  fn default() -> Self {
+    Self(0xcbf29ce484222325)
+  }
+}
+
+impl Hasher for Fnv {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for &byte in bytes {
+      self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+    }
+  }
+}
+
+/// `GL_VENDOR`, `GL_RENDERER` and `GL_VERSION` of the current context.
+pub fn driver() -> [String; 3] {
+  [gl::VENDOR, gl::RENDERER, gl::VERSION].map(|name| {
+    let string = crate::gl!(gl::GetString(name));
+    match string.is_null() {
+      true => String::new(),
+      false => unsafe { CStr::from_ptr(string as _) }.to_string_lossy().into_owned(),
+    }
+  })
+}
+
+/// Key of a program built from `sources`, in attachment order. The sources
+/// are hashed after preprocessing, so includes and defines are covered too.
+pub fn key(sources: &[(Stage, &str)], driver: &[String]) -> u64 {
+  let mut hasher = Fnv::default();
+  for (stage, text) in sources {
+    hasher.write_u32(stage.to_gl());
+    hasher.write(text.as_bytes());
+    hasher.write_u8(0);
+  }
+  for string in driver {
+    hasher.write(string.as_bytes());
+    hasher.write_u8(0);
+  }
+  hasher.finish()
+}
+
+/// Linked program binaries on disk, one `<key>.bin` file per program holding
+/// the binary format followed by the `glGetProgramBinary` data.
+#[derive(Debug, Clone)]
+pub struct ProgramCache {
+  pub dir: PathBuf,
+}
+
+impl ProgramCache {
+  pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+    Self { dir: dir.into() }
+  }
+
+  fn path(&self, key: u64) -> PathBuf {
+    self.dir.join(format!("{:016x}.bin", key))
+  }
+
+  /// Create a program from the cached binary. Returns `None` if there is
+  /// none or the driver rejects it, a rejected binary is removed.
+  pub fn load(&self, key: u64) -> Option<u32> {
+    self.load_with(key, |_| {})
+  }
+
+  /// `load`, calling `prepare` on the new program before the binary is
+  /// loaded into it, to set state such as `PROGRAM_SEPARABLE`.
+  pub fn load_with(&self, key: u64, prepare: impl FnOnce(u32)) -> Option<u32> {
+    let path = self.path(key);
+    let data = std::fs::read(&path).ok()?;
+    if data.len() < 4 {
+      let _ = std::fs::remove_file(&path);
+      return None;
+    }
+
+    let format = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let mut success = gl::FALSE as _;
+    let program = crate::gl! {
+      let program = gl::CreateProgram();
+      prepare(program);
+      gl::ProgramBinary(program, format, data[4..].as_ptr() as _, (data.len() - 4) as _);
+      gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+      program
+    };
+
+    if success == gl::TRUE as _ {
+      return Some(program);
+    }
+    crate::gl!(gl::DeleteProgram(program));
+    let _ = std::fs::remove_file(&path);
+    None
+  }
+
+  /// Save the binary of a linked program, which should have been linked
+  /// with `PROGRAM_BINARY_RETRIEVABLE_HINT` set.
+  pub fn store(&self, key: u64, program: u32) -> std::io::Result<()> {
+    let mut len = 0;
+    crate::gl!(gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut len));
+    if len <= 0 {
+      // The driver supports no binary formats
+      return Ok(());
+    }
+
+    let mut data = vec![0u8; len as usize];
+    let (mut written, mut format) = (0, 0);
+    crate::gl!(gl::GetProgramBinary(program, len, &mut written, &mut format, data.as_mut_ptr() as _));
+    data.truncate(written.max(0) as usize);
+
+    std::fs::create_dir_all(&self.dir)?;
+    let mut file = std::fs::File::create(self.path(key))?;
+    file.write_all(&format.to_le_bytes())?;
+    file.write_all(&data)
+  }
+}
@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use super::include::{find_location, IncludeErr, Source};
+
+/// A programmable stage of the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+  Vertex,
+  TessControl,
+  TessEvaluation,
+  Geometry,
+  Fragment,
+  Compute,
+}
+
+impl Stage {
+  pub const ALL: [Stage; 6] = [Self::Vertex, Self::TessControl, Self::TessEvaluation, Self::Geometry, Self::Fragment, Self::Compute];
+
+  pub fn from_gl(shader_type: u32) -> Option<Self> {
+    Self::ALL.into_iter().find(|stage| stage.to_gl() == shader_type)
+  }
+
+  pub fn to_gl(self) -> u32 {
+    match self {
+      Self::Vertex => gl::VERTEX_SHADER,
+      Self::TessControl => gl::TESS_CONTROL_SHADER,
+      Self::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+      Self::Geometry => gl::GEOMETRY_SHADER,
+      Self::Fragment => gl::FRAGMENT_SHADER,
+      Self::Compute => gl::COMPUTE_SHADER,
+    }
+  }
+
+  /// File name suffix used by the media pack, `name.<suffix>.glsl`.
+  pub fn suffix(self) -> &'static str {
+    match self {
+      Self::Vertex => "vs",
+      Self::TessControl => "tcs",
+      Self::TessEvaluation => "tes",
+      Self::Geometry => "gs",
+      Self::Fragment => "fs",
+      Self::Compute => "cs",
+    }
+  }
+
+  /// The stage of a `name.<suffix>.glsl` file.
+  pub fn from_path(path: &Path) -> Option<Self> {
+    let name = path.file_name()?.to_str()?.strip_suffix(".glsl")?;
+    let (_, suffix) = name.rsplit_once('.')?;
+    Self::ALL.into_iter().find(|stage| stage.suffix() == suffix)
+  }
+}
+
+impl Display for Stage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      Self::Vertex => "vertex",
+      Self::TessControl => "tessellation control",
+      Self::TessEvaluation => "tessellation evaluation",
+      Self::Geometry => "geometry",
+      Self::Fragment => "fragment",
+      Self::Compute => "compute",
+    };
+    write!(f, "{} shader", name)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  Info,
+  Warning,
+  Error,
+}
+
+/// One line of a compile or link log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  /// `None` for link messages.
+  pub stage:    Option<Stage>,
+  /// `None` when the location doesn't belong to a loaded file.
+  pub file:     Option<PathBuf>,
+  pub line:     Option<usize>,
+  pub severity: Severity,
+  pub message:  String,
+}
+
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if let Some(stage) = self.stage {
+      write!(f, "{}: ", stage)?;
+    }
+    match (&self.file, self.line) {
+      (Some(file), Some(line)) => write!(f, "{}:{}: ", file.display(), line)?,
+      (None, Some(line)) => write!(f, "line {}: ", line)?,
+      _ => {}
+    }
+    let severity = match self.severity {
+      Severity::Info => "info",
+      Severity::Warning => "warning",
+      Severity::Error => "error",
+    };
+    write!(f, "{}: {}", severity, self.message)
+  }
+}
+
+fn severity_word(word: &str) -> Option<Severity> {
+  match word.to_ascii_lowercase().as_str() {
+    "error" | "fatal" => Some(Severity::Error),
+    "warning" => Some(Severity::Warning),
+    "info" | "note" => Some(Severity::Info),
+    _ => None,
+  }
+}
+
+impl Diagnostic {
+  /// Parse one line of an info log. Understands the Mesa (`0:12(5): error:`),
+  /// NVIDIA (`0(12) : error C1008:`) and AMD/Intel (`ERROR: 0:12:`) styles,
+  /// anything else is kept as an `Info` message without a location.
+  pub fn parse(line: &str, stage: Option<Stage>, source: &Source) -> Self {
+    let mut rest = line.trim();
+    let mut severity = None;
+
+    // AMD and Intel lead with the severity
+    if let Some((word, tail)) = rest.split_once(':') {
+      if let Some(level) = severity_word(word).filter(|_| !word.contains(' ')) {
+        severity = Some(level);
+        rest = tail.trim_start();
+      }
+    }
+
+    let (mut file, mut line_no) = (None, None);
+    if let Some((range, string, n)) = find_location(rest) {
+      file = source.files.get(string).cloned();
+      line_no = Some(n);
+      rest = &rest[range.end..];
+      // Mesa adds the column
+      if let Some((_, tail)) = rest.strip_prefix('(').and_then(|r| r.split_once(')')) {
+        rest = tail;
+      }
+      rest = rest.trim_start_matches([' ', ':']);
+    }
+
+    // `error: ...` or `error C1008: ...`
+    if severity.is_none() {
+      if let Some((head, tail)) = rest.split_once(':') {
+        if let Some(level) = head.split_whitespace().next().and_then(severity_word) {
+          if head.split_whitespace().count() <= 2 {
+            severity = Some(level);
+            rest = tail.trim_start();
+          }
+        }
+      }
+    }
+
+    Self {
+      stage,
+      file,
+      line: line_no,
+      severity: severity.unwrap_or(Severity::Info),
+      message: rest.to_string(),
+    }
+  }
+
+  /// Every non-empty line of `log`.
+  pub fn parse_log(log: &str, stage: Option<Stage>, source: &Source) -> Vec<Self> {
+    log.lines()
+       .map(|line| line.trim_matches('\0').trim_end())
+       .filter(|line| !line.is_empty())
+       .map(|line| Self::parse(line, stage, source))
+       .collect()
+  }
+}
+
+#[derive(Debug)]
+pub enum ShaderError
+{
+  /// The source or one of its includes couldn't be read.
+  SourceErr(IncludeErr),
+  CompileErr(Stage, Vec<Diagnostic>),
+  LinkErr(Vec<Diagnostic>),
+  /// `program::load_dir` found no `name.<stage>.glsl` file in the directory.
+  NoStagesErr(PathBuf, String),
+  /// `Variants::get` was given two values for the same define.
+  DuplicateDefineErr(String),
+}
+
+impl ShaderError {
+  /// The parsed log, empty unless compiling or linking failed.
+  pub fn diagnostics(&self) -> &[Diagnostic] {
+    match self {
+      Self::SourceErr(_) | Self::NoStagesErr(..) | Self::DuplicateDefineErr(_) => &[],
+      Self::CompileErr(_, diagnostics) | Self::LinkErr(diagnostics) => diagnostics,
+    }
+  }
+}
+
+impl Display for ShaderError
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self
+    {
+      Self::SourceErr(err) => return write!(f, "{}", err),
+      Self::NoStagesErr(dir, name) => return write!(f, "{}: no `{}.<stage>.glsl` shaders", dir.display(), name),
+      Self::DuplicateDefineErr(name) => return write!(f, "`{}` is defined more than once", name),
+      Self::CompileErr(stage, _) => write!(f, "Failed to compile {}", stage)?,
+      Self::LinkErr(_) => write!(f, "Failed to link program")?,
+    }
+    for diagnostic in self.diagnostics() {
+      write!(f, "\n  {}", diagnostic)?;
+    }
+    Ok(())
+  }
+}
+
+impl Error for ShaderError
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)>
+  {
+    match self
+    {
+      Self::SourceErr(e) => Some(e),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,513 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use super::diagnostic::{Diagnostic, Severity, Stage};
+use super::include::Source;
+use super::reflect::{GlslType, Variable};
+
+/// `#version` numbers for desktop GL and, with `es`, for GLES.
+const VERSIONS: [u32; 13] = [110, 120, 130, 140, 150, 330, 400, 410, 420, 430, 440, 450, 460];
+const ES_VERSIONS: [u32; 4] = [100, 300, 310, 320];
+
+/// Qualifiers that can come before the type of a declaration.
+const QUALIFIERS: [&str; 20] = ["const", "flat", "smooth", "noperspective", "centroid", "sample", "patch", "invariant",
+                                "precise", "highp", "mediump", "lowp", "coherent", "volatile", "restrict", "readonly",
+                                "writeonly", "shared", "subroutine", "attribute"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Storage {
+  In,
+  Out,
+  Uniform,
+  Buffer,
+}
+
+impl Display for Storage {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      Self::In => "in",
+      Self::Out => "out",
+      Self::Uniform => "uniform",
+      Self::Buffer => "buffer",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+  pub number:  u32,
+  /// `core`, `compatibility` or `es`.
+  pub profile: Option<String>,
+}
+
+/// A variable declared at global scope or in a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+  pub storage:  Storage,
+  /// The type as written, a subroutine type for subroutine uniforms.
+  pub kind:     String,
+  pub name:     String,
+  /// From `layout(location = N)`, `-1` without one.
+  pub location: i32,
+  pub array:    bool,
+}
+
+impl Display for Declaration {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.location != -1 {
+      write!(f, "layout(location = {}) ", self.location)?;
+    }
+    write!(f, "{} {} {}{}", self.storage, self.kind, self.name, if self.array { "[]" } else { "" })
+  }
+}
+
+/// An interface block such as `out VS_OUT { vec2 tc; } vs_out;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+  pub storage:  Storage,
+  pub name:     String,
+  pub instance: Option<String>,
+  pub array:    bool,
+  pub members:  Vec<Declaration>,
+}
+
+impl Display for Block {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {} {{", self.storage, self.name)?;
+    for member in &self.members {
+      write!(f, " {} {}{};", member.kind, member.name, if member.array { "[]" } else { "" })?;
+    }
+    write!(f, " }}")?;
+    if let Some(instance) = &self.instance {
+      write!(f, " {}{}", instance, if self.array { "[]" } else { "" })?;
+    }
+    Ok(())
+  }
+}
+
+/// What a shader declares at global scope, found without a GL context.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Interface {
+  pub version:      Option<Version>,
+  pub declarations: Vec<Declaration>,
+  pub blocks:       Vec<Block>,
+  pub diagnostics:  Vec<Diagnostic>,
+}
+
+impl Interface {
+  /// Tokenize and parse a preprocessed shader. Function bodies are only
+  /// checked for balanced brackets, problems end up in `diagnostics`.
+  pub fn parse(source: &Source, stage: Option<Stage>) -> Self {
+    let mut parser = Parser { source, stage, interface: Self::default() };
+    let tokens = parser.tokenize();
+    parser.check_version();
+    parser.statements(&tokens);
+    parser.interface
+  }
+
+  pub fn has_errors(&self) -> bool {
+    self.diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error)
+  }
+
+  /// Inputs or outputs named and typed the way `glGetProgramResource*`
+  /// would report them, block members as `Block.member`.
+  pub fn variables(&self, storage: Storage) -> Vec<Variable> {
+    let variable = |name: String, declaration: &Declaration| Variable {
+      name,
+      kind: GlslType::from_name(&declaration.kind).unwrap_or(GlslType(0)),
+      location: declaration.location,
+      array_size: 1,
+    };
+
+    let plain = self.declarations
+                    .iter()
+                    .filter(|declaration| declaration.storage == storage)
+                    .map(|declaration| variable(declaration.name.clone(), declaration));
+    let members = self.blocks
+                      .iter()
+                      .filter(|block| block.storage == storage)
+                      .flat_map(|block| block.members.iter().map(move |member| (block, member)))
+                      .map(|(block, member)| variable(format!("{}.{}", block.name, member.name), member));
+    plain.chain(members).collect()
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+  text: String,
+  file: usize,
+  line: usize,
+}
+
+struct Parser<'a> {
+  source:    &'a Source,
+  stage:     Option<Stage>,
+  interface: Interface,
+}
+
+impl Parser<'_> {
+  fn report(&mut self, severity: Severity, file: usize, line: usize, message: String) {
+    self.interface.diagnostics.push(Diagnostic {
+      stage: self.stage,
+      file: self.source.files.get(file).cloned(),
+      line: Some(line),
+      severity,
+      message,
+    });
+  }
+
+  fn error_at(&mut self, token: &Token, message: String) {
+    self.report(Severity::Error, token.file, token.line, message);
+  }
+
+  fn tokenize(&mut self) -> Vec<Token> {
+    let chars: Vec<char> = self.source.text.chars().collect();
+    let (mut file, mut line) = (0, 1);
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+      let c = chars[i];
+      let next = chars.get(i + 1).copied();
+
+      if c == '\n' {
+        line += 1;
+        line_start = true;
+        i += 1;
+        continue;
+      }
+      if c.is_whitespace() {
+        i += 1;
+        continue;
+      }
+
+      if c == '#' && line_start {
+        let end = chars[i..].iter().position(|&c| c == '\n').map_or(chars.len(), |n| i + n);
+        let text: String = chars[i + 1..end].iter().collect();
+        self.directive(text.trim(), !tokens.is_empty(), &mut file, &mut line);
+        i = end;
+        continue;
+      }
+      line_start = false;
+
+      if c == '/' && next == Some('/') {
+        i = chars[i..].iter().position(|&c| c == '\n').map_or(chars.len(), |n| i + n);
+        continue;
+      }
+      if c == '/' && next == Some('*') {
+        let (start_file, start_line) = (file, line);
+        i += 2;
+        while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+          line += (chars[i] == '\n') as usize;
+          i += 1;
+        }
+        if i >= chars.len() {
+          self.report(Severity::Error, start_file, start_line, "unterminated comment".into());
+        }
+        i += 2;
+        continue;
+      }
+
+      let start = i;
+      if c.is_ascii_alphabetic() || c == '_' {
+        while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+          i += 1;
+        }
+      } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+        while i < chars.len() {
+          let exponent = matches!(chars[i], 'e' | 'E') && matches!(chars.get(i + 1), Some('+' | '-'));
+          i += if exponent { 2 } else if chars[i].is_ascii_alphanumeric() || chars[i] == '.' { 1 } else { break };
+        }
+      } else if "{}()[];,.=+-*/%<>!&|^~?:".contains(c) {
+        i += 1;
+      } else {
+        self.report(Severity::Error, file, line, format!("unexpected character `{}`", c));
+        i += 1;
+        continue;
+      }
+      tokens.push(Token { text: chars[start..i].iter().collect(), file, line });
+    }
+    tokens
+  }
+
+  /// Handle `#version` and the `#line`s added by the preprocessor, other
+  /// directives are ignored.
+  fn directive(&mut self, text: &str, after_tokens: bool, file: &mut usize, line: &mut usize) {
+    let mut words = text.split_whitespace();
+    match words.next() {
+      Some("line") => {
+        let number = words.next().and_then(|n| n.parse::<usize>().ok());
+        let string = words.next().and_then(|n| n.parse::<usize>().ok());
+        if let Some(number) = number {
+          // The newline ending the directive moves to `number`
+          *line = number.saturating_sub(1);
+          *file = string.unwrap_or(*file);
+        }
+      }
+      Some("version") => {
+        if after_tokens || self.interface.version.is_some() {
+          self.report(Severity::Error, *file, *line, "`#version` must come before anything else".into());
+          return;
+        }
+        let number = words.next().and_then(|n| n.parse::<u32>().ok());
+        let profile = words.next().map(String::from);
+        match number {
+          Some(number) => self.interface.version = Some(Version { number, profile }),
+          None => self.report(Severity::Error, *file, *line, format!("malformed `#{}`", text)),
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn check_version(&mut self) {
+    let Some(version) = self.interface.version.clone() else {
+      self.report(Severity::Error, 0, 1, "missing `#version`".into());
+      return;
+    };
+
+    let known = match version.profile.as_deref() {
+      None | Some("core") | Some("compatibility") => VERSIONS.contains(&version.number),
+      Some("es") => ES_VERSIONS.contains(&version.number),
+      Some(profile) => {
+        self.report(Severity::Error, 0, 1, format!("unknown profile `{}`", profile));
+        return;
+      }
+    };
+    if !known {
+      self.report(Severity::Error, 0, 1, format!("unknown GLSL version {}", version.number));
+      return;
+    }
+
+    let required = match self.stage {
+      Some(Stage::Geometry) => 150,
+      Some(Stage::TessControl | Stage::TessEvaluation) => 400,
+      Some(Stage::Compute) => 430,
+      _ => 0,
+    };
+    if version.profile.as_deref() != Some("es") && version.number < required {
+      let message = format!("{}s need `#version {}` or an extension, found {}", self.stage.unwrap(), required, version.number);
+      self.report(Severity::Warning, 0, 1, message);
+    }
+  }
+
+  /// Index of the bracket closing `tokens[open]`, reporting mismatches.
+  fn matching(&mut self, tokens: &[Token], open: usize) -> Option<usize> {
+    let mut stack = Vec::new();
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+      match token.text.as_str() {
+        "{" | "(" | "[" => stack.push(i),
+        close @ ("}" | ")" | "]") => {
+          let expected = match close {
+            "}" => "{",
+            ")" => "(",
+            _ => "[",
+          };
+          match stack.pop() {
+            Some(j) if tokens[j].text == expected => {}
+            Some(j) => {
+              let message = format!("`{}` closes `{}` from line {}", close, tokens[j].text, tokens[j].line);
+              self.error_at(token, message);
+              return None;
+            }
+            None => unreachable!(),
+          }
+          if stack.is_empty() {
+            return Some(i);
+          }
+        }
+        _ => {}
+      }
+    }
+    self.error_at(&tokens[open], format!("unclosed `{}`", tokens[open].text));
+    None
+  }
+
+  /// Split the global scope into declarations and function definitions.
+  fn statements(&mut self, tokens: &[Token]) {
+    let (mut start, mut i) = (0, 0);
+    while i < tokens.len() {
+      match tokens[i].text.as_str() {
+        ";" => {
+          self.declaration(&tokens[start..i]);
+          i += 1;
+          start = i;
+        }
+        "(" | "[" => match self.matching(tokens, i) {
+          Some(close) => i = close + 1,
+          None => return,
+        },
+        "{" => {
+          let Some(close) = self.matching(tokens, i) else { return };
+          let head = &tokens[start..i];
+          let is_struct = head.iter().any(|token| token.text == "struct");
+          let is_function = head.last().is_some_and(|token| token.text == ")");
+
+          if is_function {
+            i = close + 1;
+            start = i;
+            continue;
+          }
+          // A block or struct continues up to the `;`
+          let Some(end) = tokens[close..].iter().position(|token| token.text == ";").map(|n| close + n) else {
+            self.error_at(&tokens[close], "expected `;` after `}`".into());
+            return;
+          };
+          if !is_struct {
+            self.block(head, &tokens[i + 1..close], &tokens[close + 1..end]);
+          }
+          i = end + 1;
+          start = i;
+        }
+        "}" | ")" | "]" => {
+          self.error_at(&tokens[i], format!("unmatched `{}`", tokens[i].text));
+          return;
+        }
+        _ => i += 1,
+      }
+    }
+    if start < tokens.len() {
+      self.error_at(&tokens[start], "expected `;` before the end of the file".into());
+    }
+  }
+
+  /// Parse `layout(...) qualifiers storage`, returning the storage, the
+  /// location and the rest of the tokens.
+  fn qualifiers<'t>(&mut self, mut tokens: &'t [Token], storage: Option<Storage>) -> (Option<Storage>, i32, &'t [Token]) {
+    let (mut storage, mut location) = (storage, -1);
+    while let Some(token) = tokens.first() {
+      match token.text.as_str() {
+        "layout" => {
+          let close = tokens.iter().position(|token| token.text == ")");
+          let Some(close) = close.filter(|_| tokens.get(1).is_some_and(|token| token.text == "(")) else {
+            self.error_at(token, "expected `(...)` after `layout`".into());
+            return (None, -1, &[]);
+          };
+          let args = &tokens[2..close];
+          if let Some(n) = args.windows(3).find(|w| w[0].text == "location" && w[1].text == "=") {
+            location = n[2].text.parse().unwrap_or(-1);
+          }
+          tokens = &tokens[close + 1..];
+          continue;
+        }
+        "in" | "attribute" => storage = Some(Storage::In),
+        "out" => storage = Some(Storage::Out),
+        "uniform" => storage = Some(Storage::Uniform),
+        "buffer" => storage = Some(Storage::Buffer),
+        text if QUALIFIERS.contains(&text) => {}
+        _ => break,
+      }
+      tokens = &tokens[1..];
+    }
+    (storage, location, tokens)
+  }
+
+  /// A global declaration, only `in`, `out`, `uniform` and `buffer` ones
+  /// are kept.
+  fn declaration(&mut self, tokens: &[Token]) {
+    if tokens.first().is_some_and(|token| token.text == "precision") {
+      return;
+    }
+    let (storage, location, rest) = self.qualifiers(tokens, None);
+    let Some(storage) = storage else { return };
+    // `layout(triangles) in;` and the like
+    let Some((kind, mut rest)) = rest.split_first() else { return };
+
+    let mut type_array = false;
+    while rest.first().is_some_and(|token| token.text == "[") {
+      type_array = true;
+      rest = skip_brackets(rest);
+    }
+
+    for declarator in split_commas(rest) {
+      let Some(name) = declarator.first().filter(|token| is_identifier(&token.text)) else {
+        self.error_at(declarator.first().unwrap_or(kind), format!("expected a name after `{}`", kind.text));
+        break;
+      };
+      let array = type_array || declarator.get(1).is_some_and(|token| token.text == "[");
+      let declaration = Declaration { storage, kind: kind.text.clone(), name: name.text.clone(), location, array };
+      self.interface.declarations.push(declaration);
+    }
+  }
+
+  fn block(&mut self, head: &[Token], body: &[Token], tail: &[Token]) {
+    let (storage, _, rest) = self.qualifiers(head, None);
+    let Some(storage) = storage else { return };
+    let Some(name) = rest.first() else {
+      self.error_at(&head[0], "expected a block name".into());
+      return;
+    };
+
+    let mut members = Vec::new();
+    for member in body.split(|token| token.text == ";").filter(|member| !member.is_empty()) {
+      let (_, location, rest) = self.qualifiers(member, Some(storage));
+      members.extend(self.member(rest, storage, location));
+    }
+
+    let instance = tail.first().map(|token| token.text.clone());
+    let array = tail.get(1).is_some_and(|token| token.text == "[");
+    self.interface.blocks.push(Block { storage, name: name.text.clone(), instance, array, members });
+  }
+
+  fn member(&mut self, tokens: &[Token], storage: Storage, location: i32) -> Vec<Declaration> {
+    let Some((kind, rest)) = tokens.split_first() else { return vec![] };
+    split_commas(rest).into_iter()
+        .filter_map(|declarator| Some((declarator.first()?, declarator.get(1).is_some_and(|token| token.text == "["))))
+        .map(|(name, array)| Declaration { storage, kind: kind.text.clone(), name: name.text.clone(), location, array })
+        .collect()
+  }
+}
+
+fn is_identifier(text: &str) -> bool {
+  text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// Split declarators at the commas outside of initializers and array sizes.
+fn split_commas(tokens: &[Token]) -> Vec<&[Token]> {
+  let (mut parts, mut start, mut depth) = (Vec::new(), 0, 0);
+  for (i, token) in tokens.iter().enumerate() {
+    match token.text.as_str() {
+      "(" | "[" | "{" => depth += 1,
+      ")" | "]" | "}" => depth -= 1,
+      "," if depth == 0 => {
+        parts.push(&tokens[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(&tokens[start..]);
+  parts
+}
+
+/// Drop a leading `[...]`.
+fn skip_brackets(tokens: &[Token]) -> &[Token] {
+  let close = tokens.iter().position(|token| token.text == "]").unwrap_or(tokens.len() - 1);
+  &tokens[close + 1..]
+}
+
+/// Check that every input of `to` is written by `from`, see
+/// `pipeline::check_interface`.
+pub fn check_stages(from: (Stage, &Interface), to: (Stage, &Interface)) -> Result<(), super::pipeline::PipelineErr> {
+  super::pipeline::check_interface(from.0, &from.1.variables(Storage::Out), to.0, &to.1.variables(Storage::In))
+}
+
+/// The `.glsl` files under `dir`, sorted.
+pub fn find_files(dir: &std::path::Path) -> Vec<PathBuf> {
+  let mut files = Vec::new();
+  let mut dirs = vec![dir.to_path_buf()];
+  while let Some(dir) = dirs.pop() {
+    for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+      let path = entry.path();
+      if path.is_dir() {
+        dirs.push(path);
+      } else if path.extension().is_some_and(|ext| ext == "glsl") {
+        files.push(path);
+      }
+    }
+  }
+  files.sort();
+  files
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A shader with its `#include`s expanded.
+///
+/// Every file gets its own GLSL source string number, starting with `0` for
+/// the file that was loaded, and `#line` directives keep the line numbers of
+/// each file intact, so compiler messages can be mapped back with `map_log`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Source {
+  pub text:  String,
+  /// Files indexed by their source string number.
+  pub files: Vec<PathBuf>,
+}
+
+impl Source {
+  /// Rewrite the `string:line` locations in a compiler log to `file:line`.
+  pub fn map_log(&self, log: &str) -> String {
+    log.lines()
+       .map(|line| self.map_line(line))
+       .collect::<Vec<_>>()
+       .join("\n")
+  }
+
+  /// Insert `#define name value` lines right after the `#version` line, or
+  /// at the top without one, followed by a `#line` so the line numbers of
+  /// the file stay the same.
+  pub fn define(&mut self, defines: &[(&str, &str)]) {
+    if defines.is_empty() {
+      return;
+    }
+
+    let (mut line_no, mut string) = (1, 0);
+    let mut insert = None;
+    let mut offset = 0;
+    for line in self.text.split_inclusive('\n') {
+      offset += line.len();
+      if let Some(args) = directive(line, "line") {
+        let mut args = args.split_whitespace().map(|n| n.parse::<usize>().ok());
+        line_no = args.next().flatten().unwrap_or(line_no);
+        string = args.next().flatten().unwrap_or(string);
+        continue;
+      }
+      if directive(line, "version").is_some() {
+        insert = Some((offset, line_no + 1, string));
+        break;
+      }
+      line_no += 1;
+    }
+    let (offset, line_no, string) = insert.unwrap_or((0, 1, 0));
+
+    let mut lines = String::new();
+    if offset > 0 && !self.text[..offset].ends_with('\n') {
+      lines.push('\n');
+    }
+    for (name, value) in defines {
+      lines += format!("#define {} {}", name, value).trim_end();
+      lines.push('\n');
+    }
+    lines += &format!("#line {} {}\n", line_no, string);
+    self.text.insert_str(offset, &lines);
+  }
+
+  fn map_line(&self, line: &str) -> String {
+    match find_location(line) {
+      Some((range, string, line_no)) if string < self.files.len() => {
+        format!("{}{}:{}{}", &line[..range.start], self.files[string].display(), line_no, &line[range.end..])
+      }
+      _ => line.to_string(),
+    }
+  }
+}
+
+/// Find the source location at the start of a compiler log line, after an
+/// optional `ERROR: ` style prefix. Mesa, AMD and Intel write it as
+/// `string:line`, NVIDIA as `string(line)`. Returns the byte range of the
+/// location along with the source string and line number.
+pub(crate) fn find_location(line: &str) -> Option<(Range<usize>, usize, usize)> {
+  let start = ["ERROR: ", "WARNING: ", "INFO: "].iter()
+                                                 .find(|prefix| line.starts_with(*prefix))
+                                                 .map_or(0, |prefix| prefix.len());
+  let rest = &line[start..];
+
+  let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+  let string_len = digits(rest);
+  if string_len == 0 {
+    return None;
+  }
+  let string = rest[..string_len].parse().ok()?;
+
+  let (open, close) = match rest[string_len..].chars().next()? {
+    ':' => (1, 0),
+    '(' => (1, 1),
+    _ => return None,
+  };
+  let line_start = string_len + open;
+  let line_len = digits(&rest[line_start..]);
+  if line_len == 0 {
+    return None;
+  }
+  let line_no = rest[line_start..line_start + line_len].parse().ok()?;
+
+  let end = line_start + line_len + close;
+  if close == 1 && !rest[..end].ends_with(')') {
+    return None;
+  }
+
+  Some((start..start + end, string, line_no))
+}
+
+#[derive(Debug)]
+pub enum IncludeErr
+{
+  IoErr(PathBuf, std::io::Error),
+  SyntaxErr(PathBuf, usize),
+  NotFoundErr(PathBuf, usize, String),
+  RecursiveErr(PathBuf, usize, String),
+}
+
+impl Display for IncludeErr
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self
+    {
+      Self::IoErr(path, err) => write!(f, "{}: {}", path.display(), err),
+      Self::SyntaxErr(path, line) => write!(f, "{}:{}: expected `#include \"file\"` or `#include <file>`", path.display(), line),
+      Self::NotFoundErr(path, line, name) => write!(f, "{}:{}: can't find include `{}`", path.display(), line, name),
+      Self::RecursiveErr(path, line, name) => write!(f, "{}:{}: `{}` includes itself", path.display(), line, name),
+    }
+  }
+}
+
+impl Error for IncludeErr
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)>
+  {
+    match self
+    {
+      Self::IoErr(_, e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+/// The arguments of `#name ...` if `line` is that directive.
+fn directive<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+  let rest = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix(name)?;
+  match rest.chars().next() {
+    None => Some(""),
+    Some(c) if c.is_whitespace() || c == '"' || c == '<' => Some(rest.trim()),
+    _ => None,
+  }
+}
+
+/// Expands `#include "file"` and `#include <file>` directives.
+///
+/// Quoted names are looked up next to the including file first and then in
+/// `search_paths`, angle bracket names only in `search_paths`. A file
+/// containing `#pragma once` is expanded only the first time it's included.
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessor {
+  pub search_paths: Vec<PathBuf>,
+}
+
+struct State {
+  source: Source,
+  stack:  Vec<PathBuf>,
+  once:   HashSet<PathBuf>,
+}
+
+impl Preprocessor {
+  pub fn new(search_paths: Vec<PathBuf>) -> Self {
+    Self { search_paths }
+  }
+
+  pub fn load(&self, path: &Path) -> Result<Source, IncludeErr> {
+    let mut state = State { source: Source::default(), stack: Vec::new(), once: HashSet::new() };
+    self.expand(path, &mut state)?;
+    Ok(state.source)
+  }
+
+  fn resolve(&self, from: &Path, name: &str, quoted: bool) -> Option<PathBuf> {
+    let local = from.parent().filter(|_| quoted).map(|dir| dir.join(name));
+    local.into_iter()
+         .chain(self.search_paths.iter().map(|dir| dir.join(name)))
+         .find(|path| path.is_file())
+  }
+
+  fn expand(&self, path: &Path, state: &mut State) -> Result<(), IncludeErr> {
+    // Some of the media pack's shaders have Latin-1 copyright signs
+    let src = std::fs::read(path).map_err(|e| IncludeErr::IoErr(path.into(), e))?;
+    let src = String::from_utf8_lossy(&src);
+    let key = std::fs::canonicalize(path).unwrap_or_else(|_| path.into());
+
+    let index = state.source.files.len();
+    state.source.files.push(path.into());
+    state.stack.push(key.clone());
+
+    for (i, line) in src.lines().enumerate() {
+      if let Some(args) = directive(line, "include") {
+        let (name, quoted) = match (args.strip_prefix('"'), args.strip_prefix('<')) {
+          (Some(rest), _) => (rest.strip_suffix('"'), true),
+          (_, Some(rest)) => (rest.strip_suffix('>'), false),
+          _ => (None, false),
+        };
+        let name = name.ok_or_else(|| IncludeErr::SyntaxErr(path.into(), i + 1))?;
+        let target = self.resolve(path, name, quoted)
+                         .ok_or_else(|| IncludeErr::NotFoundErr(path.into(), i + 1, name.into()))?;
+        let target_key = std::fs::canonicalize(&target).unwrap_or_else(|_| target.clone());
+
+        if state.stack.contains(&target_key) {
+          return Err(IncludeErr::RecursiveErr(path.into(), i + 1, name.into()));
+        }
+        if state.once.contains(&target_key) {
+          // Keep the line so the numbering still matches
+          state.source.text.push('\n');
+          continue;
+        }
+
+        state.source.text += &format!("#line 1 {}\n", state.source.files.len());
+        self.expand(&target, state)?;
+        state.source.text += &format!("#line {} {}\n", i + 2, index);
+      } else if directive(line, "pragma") == Some("once") {
+        state.once.insert(key.clone());
+        state.source.text.push('\n');
+      } else {
+        state.source.text += line;
+        state.source.text.push('\n');
+      }
+    }
+
+    state.stack.pop();
+    Ok(())
+  }
+}
@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use super::diagnostic::Stage;
+use super::reflect::{variables, Variable};
+
+#[derive(Debug)]
+pub enum PipelineErr
+{
+  /// The program wasn't linked with `PROGRAM_SEPARABLE`.
+  NotSeparableErr(u32),
+  /// An input of the second stage that the first stage doesn't write.
+  MissingOutputErr(Stage, Stage, Variable),
+  /// An output of the first stage and an input of the second with different
+  /// types.
+  TypeErr(Stage, Stage, Variable, Variable),
+  /// `glValidateProgramPipeline` failed, with its info log.
+  ValidateErr(String),
+}
+
+impl Display for PipelineErr
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self
+    {
+      Self::NotSeparableErr(program) => write!(f, "Program {} was not linked as separable", program),
+      Self::MissingOutputErr(from, to, input) =>
+        write!(f, "{} input `{}` ({}) is not written by the {}", to, input.name, input.kind, from),
+      Self::TypeErr(from, to, output, input) =>
+        write!(f, "{} output `{}` is {} but {} input `{}` is {}", from, output.name, output.kind, to, input.name, input.kind),
+      Self::ValidateErr(log) => write!(f, "Failed to validate program pipeline\n{}", log.trim_end()),
+    }
+  }
+}
+
+impl Error for PipelineErr {}
+
+/// Bit of `stage` for `glUseProgramStages`.
+fn stage_bit(stage: Stage) -> u32 {
+  match stage {
+    Stage::Vertex => gl::VERTEX_SHADER_BIT,
+    Stage::TessControl => gl::TESS_CONTROL_SHADER_BIT,
+    Stage::TessEvaluation => gl::TESS_EVALUATION_SHADER_BIT,
+    Stage::Geometry => gl::GEOMETRY_SHADER_BIT,
+    Stage::Fragment => gl::FRAGMENT_SHADER_BIT,
+    Stage::Compute => gl::COMPUTE_SHADER_BIT,
+  }
+}
+
+/// Arrayed inputs such as the geometry shader's `color[]` are reported as
+/// `color[0]`, while the vertex shader output is plain `color`.
+fn base_name(name: &str) -> &str {
+  name.strip_suffix("[0]").unwrap_or(name)
+}
+
+/// Check that every input of stage `to` is written by stage `from` with the
+/// same type. Variables are matched by name, or by location if the name
+/// isn't found. Built-ins are ignored.
+pub fn check_interface(from: Stage, outputs: &[Variable], to: Stage, inputs: &[Variable]) -> Result<(), PipelineErr> {
+  for input in inputs.iter().filter(|input| !input.name.starts_with("gl_")) {
+    let output = outputs.iter()
+                        .find(|output| base_name(&output.name) == base_name(&input.name))
+                        .or_else(|| outputs.iter().find(|output| input.location != -1 && output.location == input.location));
+    match output {
+      None => return Err(PipelineErr::MissingOutputErr(from, to, input.clone())),
+      Some(output) if output.kind != input.kind => {
+        return Err(PipelineErr::TypeErr(from, to, output.clone(), input.clone()))
+      }
+      Some(_) => {}
+    }
+  }
+  Ok(())
+}
+
+/// A program pipeline object, made of stages taken from separable programs.
+/// The programs aren't owned and must outlive the pipeline's use.
+///
+/// ```ignore
+/// let mut pipeline = Pipeline::new();
+/// pipeline.use_stages(&[Stage::Vertex, Stage::TessControl, Stage::TessEvaluation], tess_program)?
+///         .use_stages(&[Stage::Fragment], debug_fs_program)?;
+/// pipeline.validate()?;
+/// pipeline.bind();
+/// ```
+#[derive(Debug)]
+pub struct Pipeline {
+  id:       u32,
+  programs: Vec<(Stage, u32)>,
+}
+
+impl Pipeline {
+  pub fn new() -> Self {
+    let mut id = 0;
+    crate::gl!(gl::CreateProgramPipelines(1, &mut id));
+    Self { id, programs: Vec::new() }
+  }
+
+  #[inline(always)]
+  pub fn id(&self) -> u32 {
+    self.id
+  }
+
+  /// Use `stages` of `program`, replacing whatever program they came from
+  /// before. `program` must have been linked separable, see
+  /// `program::Builder::separable`.
+  pub fn use_stages(&mut self, stages: &[Stage], program: u32) -> Result<&mut Self, PipelineErr> {
+    let mut separable = 0;
+    crate::gl!(gl::GetProgramiv(program, gl::PROGRAM_SEPARABLE, &mut separable));
+    if separable != gl::TRUE as _ {
+      return Err(PipelineErr::NotSeparableErr(program));
+    }
+
+    let bits = stages.iter().fold(0, |bits, &stage| bits | stage_bit(stage));
+    crate::gl!(gl::UseProgramStages(self.id, bits, program));
+    self.programs.retain(|(stage, _)| !stages.contains(stage));
+    self.programs.extend(stages.iter().map(|&stage| (stage, program)));
+    Ok(self)
+  }
+
+  /// Stop using `stages`.
+  pub fn clear_stages(&mut self, stages: &[Stage]) {
+    let bits = stages.iter().fold(0, |bits, &stage| bits | stage_bit(stage));
+    crate::gl!(gl::UseProgramStages(self.id, bits, 0));
+    self.programs.retain(|(stage, _)| !stages.contains(stage));
+  }
+
+  /// The program `stage` comes from.
+  pub fn program(&self, stage: Stage) -> Option<u32> {
+    self.programs.iter().find(|(s, _)| *s == stage).map(|&(_, program)| program)
+  }
+
+  /// Check the interfaces between consecutive stages taken from different
+  /// programs. The outputs of a program are those of its last stage, so a
+  /// program whose later stages aren't used by the pipeline can't be
+  /// checked this way.
+  pub fn check_interfaces(&self) -> Result<(), PipelineErr> {
+    let active: Vec<_> = Stage::ALL.into_iter()
+                                   .filter(|&stage| stage != Stage::Compute)
+                                   .filter_map(|stage| self.program(stage).map(|program| (stage, program)))
+                                   .collect();
+    for pair in active.windows(2) {
+      let ((from, from_program), (to, to_program)) = (pair[0], pair[1]);
+      if from_program != to_program {
+        check_interface(from,
+                        &variables(from_program, gl::PROGRAM_OUTPUT),
+                        to,
+                        &variables(to_program, gl::PROGRAM_INPUT))?;
+      }
+    }
+    Ok(())
+  }
+
+  /// `check_interfaces`, then `glValidateProgramPipeline` against the
+  /// current GL state.
+  pub fn validate(&self) -> Result<(), PipelineErr> {
+    self.check_interfaces()?;
+
+    let (mut status, mut len) = (0, 0);
+    crate::gl!(gl::ValidateProgramPipeline(self.id));
+    crate::gl!(gl::GetProgramPipelineiv(self.id, gl::VALIDATE_STATUS, &mut status));
+    if status == gl::TRUE as _ {
+      return Ok(());
+    }
+
+    crate::gl!(gl::GetProgramPipelineiv(self.id, gl::INFO_LOG_LENGTH, &mut len));
+    let mut log = vec![0u8; len.max(1) as usize];
+    let mut written = 0;
+    crate::gl!(gl::GetProgramPipelineInfoLog(self.id, len, &mut written, log.as_mut_ptr() as _));
+    log.truncate(written.max(0) as usize);
+    Err(PipelineErr::ValidateErr(String::from_utf8_lossy(&log).into_owned()))
+  }
+
+  /// Bind the pipeline. A program bound with `glUseProgram` takes
+  /// precedence, so that is reset to 0.
+  pub fn bind(&self) {
+    crate::gl!(gl::UseProgram(0));
+    crate::gl!(gl::BindProgramPipeline(self.id));
+  }
+}
+
+impl Default for Pipeline {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Drop for Pipeline {
+  fn drop(&mut self) {
+    crate::gl!(gl::DeleteProgramPipelines(1, &self.id));
+  }
+}
@@ -0,0 +1,292 @@
+use std::fmt::Display;
+
+use gl::types::GLenum;
+
+use super::diagnostic::Stage;
+
+/// GLSL type of a variable, as reported by `GL_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlslType(pub GLenum);
+
+const TYPES: &[(GLenum, &str)] = &[
+  (gl::FLOAT,                         "float"),
+  (gl::FLOAT_VEC2,                    "vec2"),
+  (gl::FLOAT_VEC3,                    "vec3"),
+  (gl::FLOAT_VEC4,                    "vec4"),
+  (gl::DOUBLE,                        "double"),
+  (gl::DOUBLE_VEC2,                   "dvec2"),
+  (gl::DOUBLE_VEC3,                   "dvec3"),
+  (gl::DOUBLE_VEC4,                   "dvec4"),
+  (gl::INT,                           "int"),
+  (gl::INT_VEC2,                      "ivec2"),
+  (gl::INT_VEC3,                      "ivec3"),
+  (gl::INT_VEC4,                      "ivec4"),
+  (gl::UNSIGNED_INT,                  "uint"),
+  (gl::UNSIGNED_INT_VEC2,             "uvec2"),
+  (gl::UNSIGNED_INT_VEC3,             "uvec3"),
+  (gl::UNSIGNED_INT_VEC4,             "uvec4"),
+  (gl::BOOL,                          "bool"),
+  (gl::BOOL_VEC2,                     "bvec2"),
+  (gl::BOOL_VEC3,                     "bvec3"),
+  (gl::BOOL_VEC4,                     "bvec4"),
+  (gl::FLOAT_MAT2,                    "mat2"),
+  (gl::FLOAT_MAT3,                    "mat3"),
+  (gl::FLOAT_MAT4,                    "mat4"),
+  (gl::FLOAT_MAT2x3,                  "mat2x3"),
+  (gl::FLOAT_MAT2x4,                  "mat2x4"),
+  (gl::FLOAT_MAT3x2,                  "mat3x2"),
+  (gl::FLOAT_MAT3x4,                  "mat3x4"),
+  (gl::FLOAT_MAT4x2,                  "mat4x2"),
+  (gl::FLOAT_MAT4x3,                  "mat4x3"),
+  (gl::DOUBLE_MAT2,                   "dmat2"),
+  (gl::DOUBLE_MAT3,                   "dmat3"),
+  (gl::DOUBLE_MAT4,                   "dmat4"),
+  (gl::SAMPLER_1D,                    "sampler1D"),
+  (gl::SAMPLER_2D,                    "sampler2D"),
+  (gl::SAMPLER_3D,                    "sampler3D"),
+  (gl::SAMPLER_CUBE,                  "samplerCube"),
+  (gl::SAMPLER_2D_SHADOW,             "sampler2DShadow"),
+  (gl::SAMPLER_1D_ARRAY,              "sampler1DArray"),
+  (gl::SAMPLER_2D_ARRAY,              "sampler2DArray"),
+  (gl::SAMPLER_2D_ARRAY_SHADOW,       "sampler2DArrayShadow"),
+  (gl::SAMPLER_CUBE_SHADOW,           "samplerCubeShadow"),
+  (gl::SAMPLER_CUBE_MAP_ARRAY,        "samplerCubeArray"),
+  (gl::SAMPLER_2D_MULTISAMPLE,        "sampler2DMS"),
+  (gl::SAMPLER_2D_MULTISAMPLE_ARRAY,  "sampler2DMSArray"),
+  (gl::SAMPLER_BUFFER,                "samplerBuffer"),
+  (gl::SAMPLER_2D_RECT,               "sampler2DRect"),
+  (gl::INT_SAMPLER_2D,                "isampler2D"),
+  (gl::INT_SAMPLER_3D,                "isampler3D"),
+  (gl::INT_SAMPLER_2D_ARRAY,          "isampler2DArray"),
+  (gl::INT_SAMPLER_BUFFER,            "isamplerBuffer"),
+  (gl::UNSIGNED_INT_SAMPLER_2D,       "usampler2D"),
+  (gl::UNSIGNED_INT_SAMPLER_3D,       "usampler3D"),
+  (gl::UNSIGNED_INT_SAMPLER_2D_ARRAY, "usampler2DArray"),
+  (gl::UNSIGNED_INT_SAMPLER_BUFFER,   "usamplerBuffer"),
+  (gl::IMAGE_1D,                      "image1D"),
+  (gl::IMAGE_2D,                      "image2D"),
+  (gl::IMAGE_3D,                      "image3D"),
+  (gl::IMAGE_2D_ARRAY,                "image2DArray"),
+  (gl::IMAGE_BUFFER,                  "imageBuffer"),
+  (gl::INT_IMAGE_2D,                  "iimage2D"),
+  (gl::UNSIGNED_INT_IMAGE_2D,         "uimage2D"),
+  (gl::UNSIGNED_INT_IMAGE_BUFFER,     "uimageBuffer"),
+  (gl::UNSIGNED_INT_ATOMIC_COUNTER,   "atomic_uint"),
+];
+
+impl GlslType {
+  pub fn name(self) -> &'static str {
+    TYPES.iter().find(|(kind, _)| *kind == self.0).map_or("unknown_type", |(_, name)| name)
+  }
+
+  /// The type called `name` in GLSL source.
+  pub fn from_name(name: &str) -> Option<Self> {
+    TYPES.iter().find(|(_, n)| *n == name).map(|&(kind, _)| Self(kind))
+  }
+}
+
+impl Display for GlslType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// A uniform in the default block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Uniform {
+  pub name:       String,
+  pub kind:       GlslType,
+  pub location:   i32,
+  pub array_size: i32,
+}
+
+/// A member of a uniform or shader storage block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockMember {
+  pub name:          String,
+  pub kind:          GlslType,
+  /// Byte offset from the start of the block.
+  pub offset:        i32,
+  pub array_size:    i32,
+  pub array_stride:  i32,
+  pub matrix_stride: i32,
+  pub row_major:     bool,
+}
+
+/// A uniform block or a shader storage block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+  pub name:      String,
+  pub binding:   i32,
+  /// Minimum size of a buffer bound to the block, in bytes.
+  pub data_size: i32,
+  /// Sorted by offset.
+  pub members:   Vec<BlockMember>,
+}
+
+/// A vertex input or fragment output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+  pub name:       String,
+  pub kind:       GlslType,
+  /// `-1` for built-ins such as `gl_VertexID`.
+  pub location:   i32,
+  pub array_size: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubroutineUniform {
+  pub name:       String,
+  pub location:   i32,
+  pub array_size: i32,
+  /// Indices of the subroutines that can be assigned to it.
+  pub compatible: Vec<u32>,
+}
+
+/// The subroutines and subroutine uniforms of one stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subroutines {
+  pub stage:     Stage,
+  /// `(name, index)` of every subroutine function.
+  pub functions: Vec<(String, u32)>,
+  pub uniforms:  Vec<SubroutineUniform>,
+}
+
+/// Everything `reflect` found out about a linked program.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramInfo {
+  pub uniforms:       Vec<Uniform>,
+  pub uniform_blocks: Vec<Block>,
+  pub storage_blocks: Vec<Block>,
+  pub inputs:         Vec<Variable>,
+  pub outputs:        Vec<Variable>,
+  /// Only stages with at least one subroutine uniform.
+  pub subroutines:    Vec<Subroutines>,
+  pub workgroup_size: Option<[i32; 3]>,
+}
+
+fn resource_count(program: u32, interface: GLenum) -> u32 {
+  let mut count = 0;
+  crate::gl!(gl::GetProgramInterfaceiv(program, interface, gl::ACTIVE_RESOURCES, &mut count));
+  count as u32
+}
+
+fn resource_props<const N: usize>(program: u32, interface: GLenum, index: u32, props: [GLenum; N]) -> [i32; N] {
+  let mut params = [0; N];
+  crate::gl!(gl::GetProgramResourceiv(program, interface, index, N as _, props.as_ptr(), N as _, std::ptr::null_mut(), params.as_mut_ptr()));
+  params
+}
+
+fn resource_name(program: u32, interface: GLenum, index: u32) -> String {
+  let [len] = resource_props(program, interface, index, [gl::NAME_LENGTH]);
+  let mut name = vec![0u8; len.max(1) as usize];
+  let mut written = 0;
+  crate::gl!(gl::GetProgramResourceName(program, interface, index, len, &mut written, name.as_mut_ptr() as _));
+  name.truncate(written.max(0) as usize);
+  String::from_utf8_lossy(&name).into_owned()
+}
+
+/// `GL_ACTIVE_VARIABLES` or `GL_COMPATIBLE_SUBROUTINES` of a resource.
+fn resource_list(program: u32, interface: GLenum, index: u32, count: i32, prop: GLenum) -> Vec<u32> {
+  let mut list = vec![0i32; count.max(0) as usize];
+  if !list.is_empty() {
+    crate::gl!(gl::GetProgramResourceiv(program, interface, index, 1, &prop, list.len() as _, std::ptr::null_mut(), list.as_mut_ptr()));
+  }
+  list.into_iter().map(|i| i as u32).collect()
+}
+
+fn blocks(program: u32, interface: GLenum, members: GLenum) -> Vec<Block> {
+  (0..resource_count(program, interface)).map(|i| {
+    let [binding, data_size, count] = resource_props(program, interface, i, [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE, gl::NUM_ACTIVE_VARIABLES]);
+    let mut members: Vec<_> = resource_list(program, interface, i, count, gl::ACTIVE_VARIABLES)
+      .into_iter()
+      .map(|m| {
+        let [kind, offset, array_size, array_stride, matrix_stride, row_major] =
+          resource_props(program, members, m, [gl::TYPE, gl::OFFSET, gl::ARRAY_SIZE, gl::ARRAY_STRIDE, gl::MATRIX_STRIDE, gl::IS_ROW_MAJOR]);
+        BlockMember {
+          name: resource_name(program, members, m),
+          kind: GlslType(kind as _),
+          offset,
+          array_size,
+          array_stride,
+          matrix_stride,
+          row_major: row_major != 0,
+        }
+      })
+      .collect();
+    members.sort_by_key(|member| member.offset);
+
+    Block { name: resource_name(program, interface, i), binding, data_size, members }
+  }).collect()
+}
+
+pub(crate) fn variables(program: u32, interface: GLenum) -> Vec<Variable> {
+  (0..resource_count(program, interface)).map(|i| {
+    let [kind, location, array_size] = resource_props(program, interface, i, [gl::TYPE, gl::LOCATION, gl::ARRAY_SIZE]);
+    Variable { name: resource_name(program, interface, i), kind: GlslType(kind as _), location, array_size }
+  }).collect()
+}
+
+fn subroutines(program: u32, stage: Stage) -> Option<Subroutines> {
+  let (functions, uniforms) = match stage {
+    Stage::Vertex => (gl::VERTEX_SUBROUTINE, gl::VERTEX_SUBROUTINE_UNIFORM),
+    Stage::TessControl => (gl::TESS_CONTROL_SUBROUTINE, gl::TESS_CONTROL_SUBROUTINE_UNIFORM),
+    Stage::TessEvaluation => (gl::TESS_EVALUATION_SUBROUTINE, gl::TESS_EVALUATION_SUBROUTINE_UNIFORM),
+    Stage::Geometry => (gl::GEOMETRY_SUBROUTINE, gl::GEOMETRY_SUBROUTINE_UNIFORM),
+    Stage::Fragment => (gl::FRAGMENT_SUBROUTINE, gl::FRAGMENT_SUBROUTINE_UNIFORM),
+    Stage::Compute => (gl::COMPUTE_SUBROUTINE, gl::COMPUTE_SUBROUTINE_UNIFORM),
+  };
+
+  let uniforms: Vec<_> = (0..resource_count(program, uniforms)).map(|i| {
+    let [location, array_size, count] = resource_props(program, uniforms, i, [gl::LOCATION, gl::ARRAY_SIZE, gl::NUM_COMPATIBLE_SUBROUTINES]);
+    SubroutineUniform {
+      name: resource_name(program, uniforms, i),
+      location,
+      array_size,
+      compatible: resource_list(program, uniforms, i, count, gl::COMPATIBLE_SUBROUTINES),
+    }
+  }).collect();
+  if uniforms.is_empty() {
+    return None;
+  }
+
+  let functions = (0..resource_count(program, functions)).map(|i| (resource_name(program, functions, i), i)).collect();
+  Some(Subroutines { stage, functions, uniforms })
+}
+
+/// The workgroup size of `program`, or `None` if it wasn't linked from a
+/// compute shader. Any other program fails the query with
+/// `GL_INVALID_OPERATION`, which is kept out of the debug output.
+fn workgroup_size(program: u32) -> Option<[i32; 3]> {
+  crate::gl! {
+    // Errors left over from earlier calls aren't this query's
+    while gl::GetError() != gl::NO_ERROR {}
+    let debug = gl::IsEnabled(gl::DEBUG_OUTPUT) == gl::TRUE;
+    gl::Disable(gl::DEBUG_OUTPUT);
+    let mut size = [0; 3];
+    gl::GetProgramiv(program, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+    let compute = gl::GetError() == gl::NO_ERROR;
+    if debug {
+      gl::Enable(gl::DEBUG_OUTPUT);
+    }
+    compute.then_some(size)
+  }
+}
+
+/// Describe the active interface of a linked program.
+pub fn reflect(program: u32) -> ProgramInfo {
+  let uniforms = (0..resource_count(program, gl::UNIFORM)).filter_map(|i| {
+    let [kind, location, array_size, block] = resource_props(program, gl::UNIFORM, i, [gl::TYPE, gl::LOCATION, gl::ARRAY_SIZE, gl::BLOCK_INDEX]);
+    (block == -1).then(|| Uniform { name: resource_name(program, gl::UNIFORM, i), kind: GlslType(kind as _), location, array_size })
+  }).collect();
+
+  ProgramInfo {
+    uniforms,
+    uniform_blocks: blocks(program, gl::UNIFORM_BLOCK, gl::UNIFORM),
+    storage_blocks: blocks(program, gl::SHADER_STORAGE_BLOCK, gl::BUFFER_VARIABLE),
+    inputs: variables(program, gl::PROGRAM_INPUT),
+    outputs: variables(program, gl::PROGRAM_OUTPUT),
+    subroutines: Stage::ALL.into_iter().filter_map(|stage| subroutines(program, stage)).collect(),
+    workgroup_size: workgroup_size(program),
+  }
+}
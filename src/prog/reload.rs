@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::builder::Builder;
+
+/// How often the watched files are checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+  std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn stamp(files: Vec<PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+  files.into_iter()
+       .map(|path| {
+         let time = modified(&path);
+         (path, time)
+       })
+       .collect()
+}
+
+#[derive(Debug)]
+struct Watched {
+  program: u32,
+  /// Files, defines, link options and assets the program is rebuilt with.
+  builder: Builder,
+  files:   Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watched {
+  fn changed(&self) -> bool {
+    self.files.iter().any(|(path, time)| modified(path) != *time)
+  }
+}
+
+/// Programs built by `AppContext::load_watched`, rebuilt by the run loop
+/// when one of their files changes.
+#[derive(Debug)]
+pub(crate) struct Reloader {
+  watched:   Vec<Watched>,
+  last_poll: Instant,
+}
+
+impl Default for Reloader {
+  fn default() -> Self {
+    Self { watched: Vec::new(), last_poll: Instant::now() }
+  }
+}
+
+impl Reloader {
+  pub(crate) fn watch(&mut self, program: u32, builder: Builder, files: Vec<PathBuf>) {
+    self.watched.push(Watched { program, builder, files: stamp(files) });
+  }
+
+  pub(crate) fn unwatch(&mut self, program: u32) {
+    self.watched.retain(|watched| watched.program != program);
+  }
+
+  /// Go back to `old` after the app didn't switch over to the rebuilt `new`.
+  pub(crate) fn revert(&mut self, new: u32, old: u32) {
+    for watched in self.watched.iter_mut().filter(|watched| watched.program == new) {
+      watched.program = old;
+    }
+  }
+
+  /// Rebuild the programs whose files changed, returning `(old, new)` for
+  /// each one that linked. A program that fails keeps its old version and
+  /// is retried after the next change.
+  pub(crate) fn poll(&mut self) -> Vec<(u32, u32)> {
+    if self.last_poll.elapsed() < POLL_INTERVAL {
+      return Vec::new();
+    }
+    self.last_poll = Instant::now();
+
+    let mut swapped = Vec::new();
+    for watched in self.watched.iter_mut().filter(|watched| watched.changed()) {
+      match watched.builder.build_with_files() {
+        Ok((program, files)) => {
+          println!("Reloaded program {} as {}", watched.program, program);
+          swapped.push((watched.program, program));
+          watched.program = program;
+          watched.files = stamp(files);
+        }
+        Err(err) => {
+          eprintln!("Keeping program {}: {}", watched.program, err);
+          for (path, time) in &mut watched.files {
+            *time = modified(path);
+          }
+        }
+      }
+    }
+    swapped
+  }
+}
@@ -0,0 +1,265 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
+
+use super::diagnostic::{ShaderError, Stage};
+use crate::vmath::{Mat2, Mat3, Mat4, VecN};
+
+/// A value that can be uploaded to a uniform with `glProgramUniform*`.
+pub trait Uniform {
+  fn upload(&self, program: u32, location: i32);
+}
+
+macro_rules! impl_uniform {
+  ($($t:ty => $f:ident($scalar:ty)),* $(,)?) => {$(
+    impl Uniform for [$t] {
+      fn upload(&self, program: u32, location: i32) {
+        crate::gl!(gl::$f(program, location, self.len() as _, self.as_ptr() as *const $scalar));
+      }
+    }
+
+    impl<const N: usize> Uniform for [$t; N] {
+      fn upload(&self, program: u32, location: i32) {
+        self[..].upload(program, location);
+      }
+    }
+
+    impl Uniform for $t {
+      fn upload(&self, program: u32, location: i32) {
+        std::slice::from_ref(self).upload(program, location);
+      }
+    }
+  )*};
+}
+
+macro_rules! impl_uniform_matrix {
+  ($($t:ty => $f:ident),* $(,)?) => {$(
+    impl Uniform for [$t] {
+      fn upload(&self, program: u32, location: i32) {
+        crate::gl!(gl::$f(program, location, self.len() as _, gl::FALSE, self.as_ptr() as *const f32));
+      }
+    }
+
+    impl<const N: usize> Uniform for [$t; N] {
+      fn upload(&self, program: u32, location: i32) {
+        self[..].upload(program, location);
+      }
+    }
+
+    impl Uniform for $t {
+      fn upload(&self, program: u32, location: i32) {
+        std::slice::from_ref(self).upload(program, location);
+      }
+    }
+  )*};
+}
+
+impl_uniform! {
+  f32          => ProgramUniform1fv(f32),
+  VecN<f32, 2> => ProgramUniform2fv(f32),
+  VecN<f32, 3> => ProgramUniform3fv(f32),
+  VecN<f32, 4> => ProgramUniform4fv(f32),
+  i32          => ProgramUniform1iv(i32),
+  VecN<i32, 2> => ProgramUniform2iv(i32),
+  VecN<i32, 3> => ProgramUniform3iv(i32),
+  VecN<i32, 4> => ProgramUniform4iv(i32),
+  u32          => ProgramUniform1uiv(u32),
+  VecN<u32, 2> => ProgramUniform2uiv(u32),
+  VecN<u32, 3> => ProgramUniform3uiv(u32),
+  VecN<u32, 4> => ProgramUniform4uiv(u32),
+}
+
+impl_uniform_matrix! {
+  Mat2 => ProgramUniformMatrix2fv,
+  Mat3 => ProgramUniformMatrix3fv,
+  Mat4 => ProgramUniformMatrix4fv,
+}
+
+impl Uniform for bool {
+  fn upload(&self, program: u32, location: i32) {
+    (*self as i32).upload(program, location);
+  }
+}
+
+/// Subroutine uniform location and function index of a selection.
+type Selection = (usize, u32);
+
+/// A linked program that looks uniform locations up by name the first time
+/// they are set. The program is deleted when dropped, so drop it in
+/// `shutdown` at the latest while the context is still current.
+#[derive(Debug, Default)]
+pub struct Program {
+  id:          u32,
+  locations:   RefCell<HashMap<String, i32>>,
+  /// `(stage, subroutine uniform, function)` as set by `set_subroutine`.
+  subroutines: Vec<(Stage, String, String)>,
+  /// Every selection made so far, `None` if either name isn't active.
+  selections:  RefCell<HashMap<(Stage, String, String), Option<Selection>>>,
+  /// Complete `glUniformSubroutinesuiv` arrays, built on first use and
+  /// updated in place when a selection changes.
+  indices:     RefCell<HashMap<Stage, Vec<u32>>>,
+}
+
+impl Program {
+  /// Take ownership of a linked program.
+  pub fn new(id: u32) -> Self {
+    Self {
+      id,
+      locations:   Default::default(),
+      subroutines: Vec::new(),
+      selections:  Default::default(),
+      indices:     Default::default(),
+    }
+  }
+
+  /// `program::try_load` wrapped up.
+  pub fn load(stages: &[(&str, Stage)]) -> Result<Self, ShaderError> {
+    super::program::try_load(stages).map(Self::new)
+  }
+
+  #[inline(always)]
+  pub fn id(&self) -> u32 {
+    self.id
+  }
+
+  /// `glUseProgram`, then the subroutines from `set_subroutine`, which GL
+  /// forgets every time the current program changes.
+  pub fn use_program(&self) {
+    crate::gl!(gl::UseProgram(self.id));
+    self.apply_subroutines();
+  }
+
+  /// Select `function` for the subroutine uniform `uniform` of `stage`.
+  /// Takes effect on the next `use_program`, or `apply_subroutines` if the
+  /// program is already in use. Inactive names are reported the first time
+  /// they are selected and skipped.
+  pub fn set_subroutine(&mut self, stage: Stage, uniform: &str, function: &str) {
+    if self.subroutines.iter().any(|(s, u, f)| *s == stage && u == uniform && f == function) {
+      return;
+    }
+    self.subroutines.retain(|(s, u, _)| !(*s == stage && u == uniform));
+    self.subroutines.push((stage, uniform.into(), function.into()));
+
+    // Arrays built before keep the other locations
+    let selection = self.selection(stage, uniform, function);
+    if let (Some(indices), Some((location, index))) = (self.indices.get_mut().get_mut(&stage), selection) {
+      if location < indices.len() {
+        indices[location] = index;
+      }
+    }
+  }
+
+  /// Upload the selected subroutines of every stage that has any, the
+  /// program must be in use.
+  pub fn apply_subroutines(&self) {
+    let stages: Vec<_> = Stage::ALL.into_iter().filter(|&stage| self.subroutines.iter().any(|(s, ..)| *s == stage)).collect();
+    for stage in stages {
+      let mut indices = self.indices.borrow_mut();
+      let indices = indices.entry(stage).or_insert_with(|| self.subroutine_indices(stage));
+      if !indices.is_empty() {
+        crate::gl!(gl::UniformSubroutinesuiv(stage.to_gl(), indices.len() as _, indices.as_ptr()));
+      }
+    }
+  }
+
+  /// One function index per subroutine uniform location of `stage`, as
+  /// `glUniformSubroutinesuiv` wants them. Locations without a selection
+  /// keep their current function.
+  fn subroutine_indices(&self, stage: Stage) -> Vec<u32> {
+    let mut count = 0;
+    crate::gl!(gl::GetProgramStageiv(self.id, stage.to_gl(), gl::ACTIVE_SUBROUTINE_UNIFORM_LOCATIONS, &mut count));
+    let mut indices: Vec<u32> = (0..count.max(0)).map(|location| {
+      let mut index = 0;
+      crate::gl!(gl::GetUniformSubroutineuiv(stage.to_gl(), location, &mut index));
+      index
+    }).collect();
+
+    for (_, uniform, function) in self.subroutines.iter().filter(|(s, ..)| *s == stage) {
+      if let Some((location, index)) = self.selection(stage, uniform, function).filter(|&(location, _)| location < indices.len()) {
+        indices[location] = index;
+      }
+    }
+    indices
+  }
+
+  /// Location of the subroutine uniform `uniform` and index of `function`
+  /// in `stage`, looked up once per pair. Warns about inactive names the
+  /// first time they are selected.
+  fn selection(&self, stage: Stage, uniform: &str, function: &str) -> Option<Selection> {
+    let key = (stage, uniform.to_owned(), function.to_owned());
+    if let Some(&selection) = self.selections.borrow().get(&key) {
+      return selection;
+    }
+
+    let cuniform = CString::new(uniform).unwrap();
+    let cfunction = CString::new(function).unwrap();
+    let location = crate::gl!(gl::GetSubroutineUniformLocation(self.id, stage.to_gl(), cuniform.as_ptr()));
+    let index = crate::gl!(gl::GetSubroutineIndex(self.id, stage.to_gl(), cfunction.as_ptr()));
+
+    let selection = match (usize::try_from(location), index) {
+      (Err(_), _) => {
+        eprintln!("Subroutine uniform `{}` is not active in the {} of program {}", uniform, stage, self.id);
+        None
+      }
+      (_, gl::INVALID_INDEX) => {
+        eprintln!("Subroutine `{}` is not active in the {} of program {}", function, stage, self.id);
+        None
+      }
+      (Ok(location), index) => Some((location, index)),
+    };
+    self.selections.borrow_mut().insert(key, selection);
+    selection
+  }
+
+  /// Location of the uniform `name`, `-1` if it isn't active. Warns about
+  /// inactive names the first time they are asked for.
+  pub fn location(&self, name: &str) -> i32 {
+    if let Some(&location) = self.locations.borrow().get(name) {
+      return location;
+    }
+
+    let cname = CString::new(name).unwrap();
+    let location = crate::gl!(gl::GetUniformLocation(self.id, cname.as_ptr()));
+    if location == -1 {
+      eprintln!("Uniform `{}` is not active in program {}", name, self.id);
+    }
+    self.locations.borrow_mut().insert(name.into(), location);
+    location
+  }
+
+  /// Set the uniform `name`, the program doesn't need to be in use. Setting
+  /// an inactive uniform does nothing.
+  pub fn set<U: Uniform + ?Sized>(&self, name: &str, value: &U) {
+    let location = self.location(name);
+    if location != -1 {
+      value.upload(self.id, location);
+    }
+  }
+
+  /// Follow `Application::on_program_reload`: switch to `new` if this is
+  /// `old` and forget the cached locations. `old` isn't deleted here, the
+  /// run loop does that when this returns `true`.
+  pub fn on_reload(&mut self, old: u32, new: u32) -> bool {
+    if self.id != old {
+      return false;
+    }
+    self.id = new;
+    self.locations.get_mut().clear();
+    self.selections.get_mut().clear();
+    self.indices.get_mut().clear();
+    true
+  }
+
+  /// Give up ownership of the GL object without deleting it.
+  pub fn into_raw(mut self) -> u32 {
+    std::mem::take(&mut self.id)
+  }
+}
+
+impl Drop for Program {
+  fn drop(&mut self) {
+    if self.id != 0 {
+      crate::gl!(gl::DeleteProgram(self.id));
+    }
+  }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use super::builder::Builder;
+use super::diagnostic::{ShaderError, Stage};
+use crate::application::Assets;
+
+type Defines = Vec<(String, String)>;
+
+/// Programs built from the same files with different `#define`s. Each set
+/// of defines is built the first time it's asked for and kept until the
+/// `Variants` is dropped, which deletes them all.
+///
+/// ```ignore
+/// let mut tonemap = Variants::new(&[("media/shaders/hdrtonemap/tonemap.vs.glsl", Stage::Vertex),
+///                                   ("media/shaders/hdrtonemap/tonemap.fs.glsl", Stage::Fragment)]);
+/// let adaptive = tonemap.get(&[("ADAPTIVE", "")])?;
+/// ```
+///
+/// See the hdrtonemap example, whose three modes are variants of one shader.
+#[derive(Debug, Default)]
+pub struct Variants {
+  stages:   Vec<(String, Stage)>,
+  assets:   Assets,
+  programs: HashMap<Defines, u32>,
+}
+
+impl Variants {
+  pub fn new(stages: &[(&str, Stage)]) -> Self {
+    Self {
+      stages:   stages.iter().map(|&(filename, stage)| (filename.into(), stage)).collect(),
+      assets:   Assets::default(),
+      programs: HashMap::new(),
+    }
+  }
+
+  /// Load the files through `assets`, such as `AppContext::assets()`.
+  pub fn assets(mut self, assets: &Assets) -> Self {
+    self.assets = assets.clone();
+    self
+  }
+
+  /// The program with `defines`. Their order doesn't matter, they are
+  /// sorted by name before being inserted. A name given twice is an error.
+  pub fn get(&mut self, defines: &[(&str, &str)]) -> Result<u32, ShaderError> {
+    let mut key: Defines = defines.iter().map(|&(name, value)| (name.into(), value.into())).collect();
+    key.sort();
+    if let Some(pair) = key.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+      return Err(ShaderError::DuplicateDefineErr(pair[0].0.clone()));
+    }
+    if let Some(&program) = self.programs.get(&key) {
+      return Ok(program);
+    }
+
+    let builder = self.stages.iter().fold(Builder::new().assets(&self.assets), |builder, (filename, stage)| {
+      builder.file(filename, *stage)
+    });
+    let program = key.iter().fold(builder, |builder, (name, value)| builder.define(name, value)).build()?;
+    self.programs.insert(key, program);
+    Ok(program)
+  }
+
+  /// Delete every variant built so far, so they are rebuilt from the files
+  /// the next time they are asked for.
+  pub fn clear(&mut self) {
+    for (_, program) in self.programs.drain() {
+      crate::gl!(gl::DeleteProgram(program));
+    }
+  }
+}
+
+impl Drop for Variants {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::application::AppContext;
+use crate::screenshot::{self, Image, ImageFormat};
+
+/// Where recorded frames are written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordOutput {
+  /// One numbered image per frame, `frame_00000.png` and so on.
+  Sequence { dir: PathBuf, format: ImageFormat },
+  /// A single uncompressed YUV4MPEG2 (4:4:4) stream.
+  Y4m(PathBuf),
+}
+
+impl RecordOutput {
+  /// `*.y4m` is recorded as a stream, anything else as a directory of PNGs.
+  pub fn from_path(path: &Path) -> Self {
+    match path.extension().and_then(|e| e.to_str()) {
+      Some("y4m") => Self::Y4m(path.into()),
+      _ => Self::Sequence { dir: path.into(), format: ImageFormat::Png },
+    }
+  }
+}
+
+/// Record every rendered frame of a run. The clock is switched to a fixed
+/// step of `1 / fps` seconds unless it already replays a script, so the
+/// output doesn't depend on how fast the machine renders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+  pub output: RecordOutput,
+  pub fps:    u32,
+}
+
+pub fn y4m_header(width: usize, height: usize, fps: u32) -> String {
+  format!("YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444\n", width, height, fps)
+}
+
+/// One `FRAME` of a 4:4:4 stream, converted with BT.601 studio range.
+pub fn y4m_frame(image: &Image) -> Vec<u8> {
+  let rgb = image.to_f32();
+  let pixels = image.width * image.height;
+
+  let mut frame = Vec::with_capacity(6 + pixels * 3);
+  frame.extend_from_slice(b"FRAME\n");
+  frame.resize(6 + pixels * 3, 0);
+
+  let (y, uv) = frame[6..].split_at_mut(pixels);
+  let (u, v) = uv.split_at_mut(pixels);
+  for (i, c) in rgb.chunks_exact(3).enumerate() {
+    let (r, g, b) = (c[0].clamp(0.0, 1.0), c[1].clamp(0.0, 1.0), c[2].clamp(0.0, 1.0));
+    y[i] = (16.0 + 65.481 * r + 128.553 * g + 24.966 * b).round() as u8;
+    u[i] = (128.0 - 37.797 * r - 74.203 * g + 112.0 * b).round() as u8;
+    v[i] = (128.0 + 112.0 * r - 93.786 * g - 18.214 * b).round() as u8;
+  }
+  frame
+}
+
+/// Writes the frames of one run.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+  recording: Recording,
+  stream:    Option<BufWriter<File>>,
+  /// Width and height given in the header of `stream`.
+  size:      (usize, usize),
+  frame:     usize,
+}
+
+impl Recorder {
+  pub(crate) fn new(recording: Recording) -> Self {
+    Self { recording, stream: None, size: (0, 0), frame: 0 }
+  }
+
+  /// Read back the frame just rendered and append it to the recording. A
+  /// stream can't change its frame size, so a resized frame ends it with an
+  /// error.
+  pub(crate) fn capture(&mut self, context: &AppContext) -> std::io::Result<()> {
+    let image = screenshot::capture(context, screenshot::Source::Frame)?;
+
+    match &self.recording.output {
+      RecordOutput::Sequence { dir, format } => {
+        let ext = match format {
+          ImageFormat::Png => "png",
+          ImageFormat::Ppm => "ppm",
+          ImageFormat::Hdr => "hdr",
+        };
+        std::fs::create_dir_all(dir)?;
+        File::create(dir.join(format!("frame_{:05}.{}", self.frame, ext)))?.write_all(&image.encode(*format))?;
+      }
+      RecordOutput::Y4m(path) => {
+        let size = (image.width, image.height);
+        if self.stream.is_none() {
+          let mut stream = BufWriter::new(File::create(path)?);
+          stream.write_all(y4m_header(image.width, image.height, self.recording.fps).as_bytes())?;
+          self.stream = Some(stream);
+          self.size = size;
+        }
+        if size != self.size {
+          return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
+                                         format!("{}: frame size changed from {}x{} to {}x{}",
+                                                 path.display(), self.size.0, self.size.1, size.0, size.1)));
+        }
+        if let Some(stream) = &mut self.stream {
+          stream.write_all(&y4m_frame(&image))?;
+        }
+      }
+    }
+
+    self.frame += 1;
+    Ok(())
+  }
+
+  pub(crate) fn finish(mut self) -> std::io::Result<usize> {
+    if let Some(stream) = &mut self.stream {
+      stream.flush()?;
+    }
+    Ok(self.frame)
+  }
+}
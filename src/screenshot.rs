@@ -0,0 +1,341 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::application::AppContext;
+
+/// Framebuffer to read a screenshot from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+  /// Whatever the app renders to: the back buffer of the window, or the
+  /// offscreen framebuffer in headless mode.
+  Frame,
+  /// `attachment` of framebuffer object `fbo`, e.g. `gl::COLOR_ATTACHMENT0`.
+  Attachment { fbo: u32, attachment: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pixels {
+  U8(Vec<u8>),
+  F32(Vec<f32>),
+}
+
+/// RGB image stored top row first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+  pub width:  usize,
+  pub height: usize,
+  pub pixels: Pixels,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+  Png,
+  Ppm,
+  /// Radiance RGBE, keeps the range of float framebuffers.
+  Hdr,
+}
+
+impl ImageFormat {
+  pub fn from_path(path: &Path) -> Option<Self> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+      "png" => Some(Self::Png),
+      "ppm" => Some(Self::Ppm),
+      "hdr" | "pic" => Some(Self::Hdr),
+      _ => None,
+    }
+  }
+}
+
+impl Image {
+  /// Reverse the row order, GL hands rows over bottom first.
+  pub fn flip_rows(&mut self) {
+    let stride = self.width * 3;
+    fn flip<T>(data: &mut [T], stride: usize) {
+      let rows = data.len() / stride.max(1);
+      for y in 0..rows / 2 {
+        let (top, bottom) = data.split_at_mut((rows - 1 - y) * stride);
+        top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+      }
+    }
+    match &mut self.pixels {
+      Pixels::U8(data) => flip(data, stride),
+      Pixels::F32(data) => flip(data, stride),
+    }
+  }
+
+  /// 8-bit channels, float values are clamped to `[0, 1]`.
+  pub fn to_u8(&self) -> Vec<u8> {
+    match &self.pixels {
+      Pixels::U8(data) => data.clone(),
+      Pixels::F32(data) => data.iter().map(|v| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8).collect(),
+    }
+  }
+
+  pub fn to_f32(&self) -> Vec<f32> {
+    match &self.pixels {
+      Pixels::U8(data) => data.iter().map(|&v| v as f32 / 255.0).collect(),
+      Pixels::F32(data) => data.clone(),
+    }
+  }
+
+  pub fn encode(&self, format: ImageFormat) -> Vec<u8> {
+    match format {
+      ImageFormat::Png => encode_png(self),
+      ImageFormat::Ppm => encode_ppm(self),
+      ImageFormat::Hdr => encode_hdr(self),
+    }
+  }
+
+  /// Write the image in the format matching the extension of `path`.
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let format = ImageFormat::from_path(path).ok_or_else(|| {
+      std::io::Error::new(std::io::ErrorKind::InvalidInput,
+                          format!("{}: unknown image format", path.display()))
+    })?;
+    std::fs::File::create(path)?.write_all(&self.encode(format))
+  }
+}
+
+/// Width and height of the image attached to `attachment` of `fbo`.
+fn attachment_size(fbo: u32, attachment: u32) -> (usize, usize) {
+  let (mut kind, mut name, mut level) = (0, 0, 0);
+  let (mut width, mut height) = (0, 0);
+  crate::gl! {
+    gl::GetNamedFramebufferAttachmentParameteriv(fbo, attachment, gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE, &mut kind);
+    gl::GetNamedFramebufferAttachmentParameteriv(fbo, attachment, gl::FRAMEBUFFER_ATTACHMENT_OBJECT_NAME, &mut name);
+    match kind as u32 {
+      gl::RENDERBUFFER => {
+        gl::GetNamedRenderbufferParameteriv(name as _, gl::RENDERBUFFER_WIDTH, &mut width);
+        gl::GetNamedRenderbufferParameteriv(name as _, gl::RENDERBUFFER_HEIGHT, &mut height);
+      }
+      gl::TEXTURE => {
+        gl::GetNamedFramebufferAttachmentParameteriv(fbo, attachment, gl::FRAMEBUFFER_ATTACHMENT_TEXTURE_LEVEL, &mut level);
+        gl::GetTextureLevelParameteriv(name as _, level, gl::TEXTURE_WIDTH, &mut width);
+        gl::GetTextureLevelParameteriv(name as _, level, gl::TEXTURE_HEIGHT, &mut height);
+      }
+      _ => {}
+    }
+  }
+  (width as _, height as _)
+}
+
+/// Read back the color buffer of `source`. Float attachments keep their
+/// full range, multisampled framebuffers are resolved first. A depth
+/// attachment is read as gray floats, integer and stencil attachments have
+/// no image to show and are an error.
+///
+/// `Source::Frame` is the window, or the offscreen framebuffer in headless
+/// mode, of `context`.
+pub fn capture(context: &AppContext, source: Source) -> std::io::Result<Image> {
+  let (fbo, attachment, width, height) = match source {
+    Source::Frame => {
+      let (width, height) = context.framebuffer_size();
+      match context.offscreen_fbo() {
+        Some(fbo) => (fbo, gl::COLOR_ATTACHMENT0, width as usize, height as usize),
+        None => (0, gl::BACK, width as usize, height as usize),
+      }
+    }
+    Source::Attachment { fbo, attachment } => {
+      let (width, height) = attachment_size(fbo, attachment);
+      (fbo, attachment, width, height)
+    }
+  };
+
+  let unsupported = |what: &str| {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, format!("Can't capture {} attachments", what))
+  };
+  let is_depth = matches!(attachment, gl::DEPTH_ATTACHMENT | gl::DEPTH_STENCIL_ATTACHMENT | gl::DEPTH);
+  if matches!(attachment, gl::STENCIL_ATTACHMENT | gl::STENCIL) {
+    return Err(unsupported("stencil"));
+  }
+
+  let mut component_type = 0;
+  let mut sample_buffers = 0;
+  crate::gl! {
+    if fbo != 0 {
+      gl::GetNamedFramebufferAttachmentParameteriv(fbo,
+                                                   attachment,
+                                                   gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE,
+                                                   &mut component_type);
+    }
+    gl::GetNamedFramebufferParameteriv(fbo, gl::SAMPLE_BUFFERS, &mut sample_buffers);
+  }
+  if matches!(component_type as u32, gl::INT | gl::UNSIGNED_INT) {
+    return Err(unsupported("integer"));
+  }
+  // Resolving depth would need a renderbuffer of the very same format
+  if is_depth && sample_buffers > 0 {
+    return Err(unsupported("multisampled depth"));
+  }
+  let is_float = is_depth || component_type as u32 == gl::FLOAT;
+
+  let (mut previous_read, mut previous_draw) = (0, 0);
+  crate::gl! {
+    gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut previous_read);
+    gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_draw);
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, fbo);
+  }
+
+  // glReadPixels can't read from a multisampled framebuffer object
+  let mut resolve = [0u32; 2];
+  crate::gl! {
+    if sample_buffers > 0 {
+      gl::GenRenderbuffers(1, &mut resolve[1]);
+      gl::BindRenderbuffer(gl::RENDERBUFFER, resolve[1]);
+      gl::RenderbufferStorage(gl::RENDERBUFFER, if is_float { gl::RGBA32F } else { gl::RGBA8 }, width as _, height as _);
+      gl::GenFramebuffers(1, &mut resolve[0]);
+      gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, resolve[0]);
+      gl::FramebufferRenderbuffer(gl::DRAW_FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, resolve[1]);
+      gl::ReadBuffer(attachment);
+      gl::BlitFramebuffer(0, 0, width as _, height as _, 0, 0, width as _, height as _, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+      gl::BindFramebuffer(gl::READ_FRAMEBUFFER, resolve[0]);
+      gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+    } else if !is_depth {
+      // Depth is read from the depth attachment whatever the read buffer is
+      gl::ReadBuffer(attachment);
+    }
+    gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+  }
+
+  let mut image = Image {
+    width,
+    height,
+    pixels: match is_float {
+      true => Pixels::F32(vec![0.0; width * height * 3]),
+      false => Pixels::U8(vec![0; width * height * 3]),
+    },
+  };
+
+  crate::gl! {
+    match &mut image.pixels {
+      Pixels::F32(data) if is_depth => {
+        let mut depth = vec![0.0f32; width * height];
+        gl::ReadPixels(0, 0, width as _, height as _, gl::DEPTH_COMPONENT, gl::FLOAT, depth.as_mut_ptr() as _);
+        *data = depth.into_iter().flat_map(|d| [d; 3]).collect();
+      }
+      Pixels::U8(data) => gl::ReadPixels(0, 0, width as _, height as _, gl::RGB, gl::UNSIGNED_BYTE, data.as_mut_ptr() as _),
+      Pixels::F32(data) => gl::ReadPixels(0, 0, width as _, height as _, gl::RGB, gl::FLOAT, data.as_mut_ptr() as _),
+    }
+
+    if resolve[0] != 0 {
+      gl::DeleteFramebuffers(1, &resolve[0]);
+      gl::DeleteRenderbuffers(1, &resolve[1]);
+    }
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, previous_read as _);
+    gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, previous_draw as _);
+  }
+
+  image.flip_rows();
+  Ok(image)
+}
+
+/// Screenshot queued through `AppContext::screenshot`, taken by the run loop
+/// once `render` returns.
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+  pub path:   PathBuf,
+  pub source: Source,
+}
+
+impl Request {
+  pub(crate) fn take(self, context: &AppContext) {
+    match capture(context, self.source).and_then(|image| image.save(&self.path)) {
+      Ok(()) => println!("Saved screenshot to {}", self.path.display()),
+      Err(err) => eprintln!("Failed to save screenshot: {}", err),
+    }
+  }
+}
+
+fn crc32(chunks: &[&[u8]]) -> u32 {
+  let mut crc = !0u32;
+  for &byte in chunks.iter().flat_map(|c| c.iter()) {
+    crc ^= byte as u32;
+    for _ in 0..8 {
+      crc = match crc & 1 {
+        1 => (crc >> 1) ^ 0xEDB88320,
+        _ => crc >> 1,
+      };
+    }
+  }
+  !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+  let (mut a, mut b) = (1u32, 0u32);
+  for chunk in data.chunks(5552) {
+    for &byte in chunk {
+      a += byte as u32;
+      b += a;
+    }
+    a %= 65521;
+    b %= 65521;
+  }
+  (b << 16) | a
+}
+
+/// PNG with the pixel data in uncompressed deflate blocks, which keeps the
+/// encoder free of a zlib dependency.
+pub fn encode_png(image: &Image) -> Vec<u8> {
+  let rgb = image.to_u8();
+  let stride = image.width * 3;
+
+  let mut raw = Vec::with_capacity((stride + 1) * image.height);
+  for row in rgb.chunks(stride.max(1)).take(image.height) {
+    raw.push(0); // filter: none
+    raw.extend_from_slice(row);
+  }
+
+  let mut zlib = vec![0x78, 0x01];
+  let mut blocks = raw.chunks(0xFFFF).peekable();
+  if blocks.peek().is_none() {
+    zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+  }
+  while let Some(block) = blocks.next() {
+    let len = block.len() as u16;
+    zlib.push(blocks.peek().is_none() as u8);
+    zlib.extend_from_slice(&len.to_le_bytes());
+    zlib.extend_from_slice(&(!len).to_le_bytes());
+    zlib.extend_from_slice(block);
+  }
+  zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+  let mut ihdr = Vec::with_capacity(13);
+  ihdr.extend_from_slice(&(image.width as u32).to_be_bytes());
+  ihdr.extend_from_slice(&(image.height as u32).to_be_bytes());
+  ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB
+
+  let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+  for (kind, data) in [(b"IHDR", &ihdr[..]), (b"IDAT", &zlib[..]), (b"IEND", &[][..])] {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(&[kind, data]).to_be_bytes());
+  }
+  png
+}
+
+/// Binary 8-bit PPM (P6).
+pub fn encode_ppm(image: &Image) -> Vec<u8> {
+  let mut ppm = format!("P6\n{} {}\n255\n", image.width, image.height).into_bytes();
+  ppm.extend_from_slice(&image.to_u8());
+  ppm
+}
+
+/// Radiance HDR with flat (not run-length encoded) RGBE scanlines.
+pub fn encode_hdr(image: &Image) -> Vec<u8> {
+  let mut hdr = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", image.height, image.width).into_bytes();
+  for rgb in image.to_f32().chunks_exact(3) {
+    let v = rgb[0].max(rgb[1]).max(rgb[2]);
+    if v < 1e-32 {
+      hdr.extend_from_slice(&[0; 4]);
+    } else {
+      // v = m * 2^e with m in [0.5, 1)
+      let e = v.log2().floor() as i32 + 1;
+      let scale = 256.0 / 2f32.powi(e);
+      hdr.extend(rgb.iter().map(|c| (c.max(0.0) * scale).min(255.0) as u8));
+      hdr.push((e + 128) as u8);
+    }
+  }
+  hdr
+}
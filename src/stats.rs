@@ -0,0 +1,149 @@
+use imgui_glfw_rs::imgui;
+
+/// Frames a timer query may stay in flight before its slot is reused, so
+/// reading results back never waits on the GPU.
+const QUERY_LATENCY: usize = 4;
+
+/// Rolling window of the most recent samples, in milliseconds.
+#[derive(Debug, Clone)]
+pub struct History {
+  samples:  Vec<f32>,
+  capacity: usize,
+}
+
+impl Default for History {
+  fn default() -> Self {
+    Self::new(120)
+  }
+}
+
+impl History {
+  /// Keep the last `capacity` samples, always at least one.
+  pub fn new(capacity: usize) -> Self {
+    let capacity = capacity.max(1);
+    Self { samples: Vec::with_capacity(capacity), capacity }
+  }
+
+  pub fn push(&mut self, sample: f32) {
+    if self.samples.len() == self.capacity {
+      self.samples.remove(0);
+    }
+    self.samples.push(sample);
+  }
+
+  /// Oldest sample first.
+  #[inline(always)]
+  pub fn samples(&self) -> &[f32] {
+    &self.samples
+  }
+
+  #[inline(always)]
+  pub fn last(&self) -> Option<f32> {
+    self.samples.last().copied()
+  }
+
+  pub fn average(&self) -> Option<f32> {
+    match self.samples.len() {
+      0 => None,
+      n => Some(self.samples.iter().sum::<f32>() / n as f32),
+    }
+  }
+
+  pub fn max(&self) -> Option<f32> {
+    self.samples.iter().copied().reduce(f32::max)
+  }
+}
+
+/// CPU and GPU timings of the last frames, collected by the run loop.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+  /// Time between the start of consecutive frames.
+  pub interval: History,
+  /// CPU time spent in `render` and `ui`.
+  pub cpu: History,
+  /// GPU time of the same work, measured with timestamp queries.
+  pub gpu: History,
+  /// Draw the overlay on top of the app's own ui.
+  pub show_overlay: bool,
+
+  queries:     Vec<[u32; 2]>,
+  pending:     [bool; QUERY_LATENCY],
+  frame:       usize,
+  frame_start: Option<f64>,
+}
+
+impl FrameStats {
+  pub(crate) fn begin_frame(&mut self, now: f64) {
+    if let Some(last) = self.frame_start.replace(now) {
+      self.interval.push(((now - last) * 1000.0) as f32);
+    }
+
+    if self.queries.is_empty() {
+      self.queries = vec![[0; 2]; QUERY_LATENCY];
+      crate::gl!(gl::GenQueries((QUERY_LATENCY * 2) as _, self.queries.as_mut_ptr() as *mut u32));
+    }
+
+    let slot = self.frame % QUERY_LATENCY;
+    crate::gl!(gl::QueryCounter(self.queries[slot][0], gl::TIMESTAMP));
+  }
+
+  pub(crate) fn end_frame(&mut self, now: f64) {
+    if let Some(start) = self.frame_start {
+      self.cpu.push(((now - start) * 1000.0) as f32);
+    }
+
+    let slot = self.frame % QUERY_LATENCY;
+    crate::gl!(gl::QueryCounter(self.queries[slot][1], gl::TIMESTAMP));
+    self.pending[slot] = true;
+    self.frame += 1;
+
+    // The oldest slot is the next one to be reused
+    let oldest = self.frame % QUERY_LATENCY;
+    if self.pending[oldest] {
+      let [start, end] = self.queries[oldest];
+      let mut available = 0;
+      crate::gl!(gl::GetQueryObjectiv(end, gl::QUERY_RESULT_AVAILABLE, &mut available));
+      if available != 0 {
+        let (mut t0, mut t1) = (0u64, 0u64);
+        crate::gl! {
+          gl::GetQueryObjectui64v(start, gl::QUERY_RESULT, &mut t0);
+          gl::GetQueryObjectui64v(end, gl::QUERY_RESULT, &mut t1);
+        }
+        self.gpu.push(t1.saturating_sub(t0) as f32 / 1.0e6);
+      }
+      self.pending[oldest] = false;
+    }
+  }
+
+  pub(crate) fn draw_overlay(&self, ui: &imgui::Ui) {
+    let win = imgui::Window::new("Frame statistics")
+      .position([10.0, 10.0], imgui::Condition::FirstUseEver)
+      .always_auto_resize(true)
+      .bg_alpha(0.6);
+
+    if let Some(end) = win.begin(ui) {
+      for (label, history) in [("CPU", &self.cpu), ("GPU", &self.gpu)] {
+        let text = format!("{} {:.2} ms (avg {:.2}, max {:.2})",
+                           label,
+                           history.last().unwrap_or(0.0),
+                           history.average().unwrap_or(0.0),
+                           history.max().unwrap_or(0.0));
+        ui.plot_lines(label, history.samples())
+          .graph_size([240.0, 40.0])
+          .scale_min(0.0)
+          .overlay_text(&text)
+          .build();
+      }
+      let fps = self.interval.average().map_or(0.0, |ms| 1000.0 / ms);
+      ui.text(format!("{:.1} fps", fps));
+      end.end();
+    }
+  }
+
+  pub(crate) fn free(&mut self) {
+    if !self.queries.is_empty() {
+      crate::gl!(gl::DeleteQueries((QUERY_LATENCY * 2) as _, self.queries.as_ptr() as *const u32));
+      self.queries.clear();
+    }
+  }
+}
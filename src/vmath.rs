@@ -1,5 +1,6 @@
 use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub};
 
+#[repr(C)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct VecN<T, const LEN: usize> {
   a: [T; LEN],
@@ -186,6 +187,7 @@ pub fn refract(rin: Vec3, n: Vec3, ir: f32) -> Vec3 {
   }
 }
 
+#[repr(C)]
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct MatNM<T, const W: usize, const H: usize> {
   a: [VecN<T, H>; W],
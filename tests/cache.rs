@@ -0,0 +1,15 @@
+use sb7::program::cache::key;
+use sb7::shader::Stage;
+
+#[test]
+fn keys() {
+  let driver = ["Mesa".to_string(), "llvmpipe".to_string(), "4.6 (Core Profile) Mesa 24.0".to_string()];
+  let sources = [(Stage::Vertex, "void main() {}"), (Stage::Fragment, "void main() {}")];
+  let base = key(&sources, &driver);
+
+  assert_eq!(base, key(&sources, &driver));
+  // Any change to a source, the stage order or the driver is a miss
+  assert_ne!(base, key(&[(Stage::Vertex, "void main() { }"), sources[1]], &driver));
+  assert_ne!(base, key(&[sources[1], sources[0]], &driver));
+  assert_ne!(base, key(&sources, &[driver[0].clone(), driver[1].clone(), "4.6 (Core Profile) Mesa 24.1".into()]));
+}
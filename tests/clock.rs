@@ -0,0 +1,38 @@
+use sb7::clock::{Clock, TimeSource};
+
+#[test]
+fn fixed_step() {
+  let mut clock = Clock::new(TimeSource::FixedStep(0.5));
+  assert_eq!(clock.tick(10.0), 0.0);
+  assert_eq!(clock.tick(10.1), 0.5);
+  assert_eq!(clock.tick(13.7), 1.0);
+  assert_eq!(clock.frame(), 2);
+}
+
+#[test]
+fn scripted() {
+  let mut clock = Clock::new(TimeSource::Scripted(vec![1.0, 2.5, 4.0]));
+  let times: Vec<f64> = (0..5).map(|i| clock.tick(i as f64)).collect();
+  assert_eq!(times, [1.0, 2.5, 4.0, 4.0, 4.0]);
+}
+
+#[test]
+fn pause_and_step() {
+  let mut clock = Clock::new(TimeSource::RealTime);
+  assert_eq!(clock.tick(3.0), 0.0);
+  assert_eq!(clock.tick(4.0), 1.0);
+
+  clock.set_paused(true);
+  assert_eq!(clock.tick(5.0), 1.0);
+  assert_eq!(clock.tick(6.0), 1.0);
+
+  // A single step advances by the real time of that frame only
+  clock.step();
+  assert_eq!(clock.tick(6.5), 1.5);
+  assert_eq!(clock.tick(7.0), 1.5);
+  assert!(clock.is_paused());
+
+  // Time spent paused is skipped after resuming
+  clock.set_paused(false);
+  assert_eq!(clock.tick(7.25), 1.75);
+}
@@ -0,0 +1,60 @@
+use sb7::config::{ConfigErr, Overrides};
+
+fn args(s: &str) -> Vec<String> {
+  s.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn from_args() {
+  let o = Overrides::from_args(args("--width 1280 --height=720 --vsync --no-fullscreen --media-dir ../media")).unwrap();
+  assert_eq!(o.width, Some(1280));
+  assert_eq!(o.height, Some(720));
+  assert_eq!(o.vsync, Some(true));
+  assert_eq!(o.fullscreen, Some(false));
+  assert_eq!(o.media_dir.as_deref(), Some("../media"));
+  assert_eq!(o.samples, None);
+
+  assert!(matches!(Overrides::from_args(args("--width")), Err(ConfigErr::MissingValue(_))));
+  assert!(matches!(Overrides::from_args(args("--width wide")), Err(ConfigErr::BadValue(..))));
+  assert!(matches!(Overrides::from_args(args("--record_fps 0")), Err(ConfigErr::BadValue(..))));
+  assert!(matches!(Overrides::from_args(args("--colour red")), Err(ConfigErr::UnknownOption(_))));
+  assert!(matches!(Overrides::from_args(args("--help")), Err(ConfigErr::HelpRequested)));
+}
+
+#[test]
+fn parse_and_merge() {
+  let file = Overrides::parse("
+    # sb7.ini
+    [window]
+    width = 1024
+    samples = 4
+    vsync = off
+    media_dir = \"/opt/sb7/media\"
+  ").unwrap();
+  assert_eq!(file.width, Some(1024));
+  assert_eq!(file.samples, Some(4));
+  assert_eq!(file.vsync, Some(false));
+  assert_eq!(file.media_dir.as_deref(), Some("/opt/sb7/media"));
+
+  let merged = file.merge(Overrides::from_args(args("--width 640 --frames 3")).unwrap());
+  assert_eq!(merged.width, Some(640));
+  assert_eq!(merged.samples, Some(4));
+  assert_eq!(merged.frames, Some(3));
+
+  assert!(matches!(Overrides::parse("width 1024"), Err(ConfigErr::SyntaxErr(_, 1))));
+
+  let record = Overrides::from_args(args("--record out.y4m --record-fps 30")).unwrap();
+  assert_eq!(record.record.as_deref(), Some(std::path::Path::new("out.y4m")));
+  assert_eq!(record.record_fps, Some(30));
+}
+
+#[test]
+fn from_args_lenient() {
+  let o = Overrides::from_args_lenient(args("scene.obj --test-threads 1 --width 640 --colour=red --no-vsync")).unwrap();
+  assert_eq!(o.width, Some(640));
+  assert_eq!(o.vsync, Some(false));
+  assert_eq!(o.frames, None);
+
+  assert!(matches!(Overrides::from_args_lenient(args("--width wide")), Err(ConfigErr::BadValue(..))));
+  assert!(matches!(Overrides::from_args(args("scene.obj")), Err(ConfigErr::UnknownOption(_))));
+}
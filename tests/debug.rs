@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+
+use sb7::debug::{DebugLog, DebugMessage, DebugOutput, Severity};
+
+thread_local! {
+  static SEEN: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+fn sink(msg: &DebugMessage) {
+  SEEN.with(|seen| seen.borrow_mut().push(msg.id));
+}
+
+fn message(id: u32, severity: Severity, text: &str) -> DebugMessage<'_> {
+  DebugMessage { source: gl::DEBUG_SOURCE_API, kind: gl::DEBUG_TYPE_ERROR, id, severity, text }
+}
+
+/// Ids of the messages that made it through `log`.
+fn report(log: &mut DebugLog, messages: &[DebugMessage]) -> Vec<u32> {
+  SEEN.with(|seen| seen.borrow_mut().clear());
+  for msg in messages {
+    log.report(msg);
+  }
+  SEEN.with(|seen| seen.take())
+}
+
+#[test]
+fn severity_threshold() {
+  let mut log = DebugLog::new(DebugOutput { min_severity: Severity::Medium, dedup: false, sink });
+  let messages = [
+    message(1, Severity::Notification, "a"),
+    message(2, Severity::Low, "b"),
+    message(3, Severity::Medium, "c"),
+    message(4, Severity::High, "d"),
+  ];
+  assert_eq!(report(&mut log, &messages), [3, 4]);
+}
+
+#[test]
+fn repeated_messages() {
+  let messages = [
+    message(1, Severity::High, "a"),
+    message(1, Severity::High, "a"),
+    // Same id, different text
+    message(1, Severity::High, "b"),
+    message(2, Severity::High, "a"),
+    message(1, Severity::High, "a"),
+  ];
+
+  let mut log = DebugLog::new(DebugOutput { min_severity: Severity::Low, dedup: true, sink });
+  assert_eq!(report(&mut log, &messages), [1, 1, 2]);
+  // Nothing is new the second time around
+  assert_eq!(report(&mut log, &messages), []);
+
+  let mut log = DebugLog::new(DebugOutput { min_severity: Severity::Low, dedup: false, sink });
+  assert_eq!(report(&mut log, &messages), [1, 1, 1, 2, 1]);
+}
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use sb7::include::Source;
+use sb7::shader::{Diagnostic, Severity, Stage};
+
+fn source() -> Source {
+  Source { text: String::new(), files: vec!["main.fs.glsl".into(), "common.glsl".into()] }
+}
+
+#[test]
+fn vendor_formats() {
+  let stage = Some(Stage::Fragment);
+  let parse = |line| Diagnostic::parse(line, stage, &source());
+
+  let mesa = parse("1:12(5): error: `color' undeclared");
+  assert_eq!(mesa, Diagnostic { stage,
+                                file: Some(PathBuf::from("common.glsl")),
+                                line: Some(12),
+                                severity: Severity::Error,
+                                message: "`color' undeclared".into() });
+
+  let nvidia = parse("0(3) : warning C7050: \"x\" might be used before being initialized");
+  assert_eq!((nvidia.file, nvidia.line, nvidia.severity), (Some("main.fs.glsl".into()), Some(3), Severity::Warning));
+  assert_eq!(nvidia.message, "\"x\" might be used before being initialized");
+
+  let amd = parse("ERROR: 0:7: 'vec5' : syntax error");
+  assert_eq!((amd.line, amd.severity, amd.message.as_str()), (Some(7), Severity::Error, "'vec5' : syntax error"));
+
+  // Unknown source strings keep the line but lose the file
+  let unknown = parse("4:2(1): error: oops");
+  assert_eq!((unknown.file, unknown.line), (None, Some(2)));
+
+  let link = Diagnostic::parse("error: linking with uncompiled shader", None, &Source::default());
+  assert_eq!((link.line, link.severity, link.message.as_str()), (None, Severity::Error, "linking with uncompiled shader"));
+}
+
+#[test]
+fn full_log() {
+  let log = "Fragment info\n-------------\n0(3) : error C0000: syntax error\n\0";
+  let diagnostics = Diagnostic::parse_log(log, Some(Stage::Fragment), &source());
+  assert_eq!(diagnostics.len(), 3);
+  assert_eq!(diagnostics[0].severity, Severity::Info);
+  assert_eq!(diagnostics[2].to_string(), "fragment shader: main.fs.glsl:3: error: syntax error");
+}
+
+#[test]
+fn stage_from_path() {
+  assert_eq!(Stage::from_path("media/shaders/dispmap/dispmap.tcs.glsl".as_ref()), Some(Stage::TessControl));
+  assert_eq!(Stage::from_path("prefixsum.cs.glsl".as_ref()), Some(Stage::Compute));
+  assert_eq!(Stage::from_path("common.glsl".as_ref()), None);
+  assert_eq!(Stage::from_path("dispmap.vs".as_ref()), None);
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use sb7::glsl::{check_stages, Interface, Storage};
+use sb7::include::Source;
+use sb7::shader::{Severity, Stage};
+
+fn parse(text: &str, stage: Stage) -> Interface {
+  let source = Source { text: text.into(), files: vec!["test.glsl".into()] };
+  Interface::parse(&source, Some(stage))
+}
+
+#[test]
+fn declarations() {
+  let vs = parse("#version 450 core
+                  /* uniforms */
+                  layout (location = 0) in vec4 position;
+                  uniform vec3 light_pos = vec3(30.0, 30.0, 100.0), eye;
+                  layout (std140, binding = 0) uniform Transforms { mat4 mv; mat4 proj[2]; };
+                  out VS_OUT { vec3 normal; } vs_out;
+                  struct Light { vec3 color; };
+                  void main(void) { if (true) { vs_out.normal = vec3(0); } }",
+                 Stage::Vertex);
+  assert!(vs.diagnostics.is_empty(), "{:?}", vs.diagnostics);
+  assert_eq!(vs.version.as_ref().map(|version| version.number), Some(450));
+
+  let names: Vec<_> = vs.declarations.iter().map(|declaration| declaration.to_string()).collect();
+  assert_eq!(names, ["layout(location = 0) in vec4 position", "uniform vec3 light_pos", "uniform vec3 eye"]);
+  assert_eq!(vs.blocks[0].to_string(), "uniform Transforms { mat4 mv; mat4 proj[]; }");
+  assert_eq!(vs.blocks[1].to_string(), "out VS_OUT { vec3 normal; } vs_out");
+  assert_eq!(vs.variables(Storage::Out)[0].name, "VS_OUT.normal");
+}
+
+#[test]
+fn errors() {
+  let fs = parse("out vec4 color;\nvoid main(void) {\n  color = vec4(1;\n}\n", Stage::Fragment);
+  let messages: Vec<_> = fs.diagnostics.iter().map(|diagnostic| (diagnostic.line, diagnostic.message.as_str())).collect();
+  assert_eq!(messages, [(Some(1), "missing `#version`"), (Some(4), "`}` closes `(` from line 3")]);
+  assert_eq!(fs.diagnostics[0].file, Some(PathBuf::from("test.glsl")));
+
+  let tcs = parse("#version 330 core\nlayout (vertices = 3) out;", Stage::TessControl);
+  assert_eq!(tcs.diagnostics[0].severity, Severity::Warning);
+  assert!(!tcs.has_errors());
+
+  // `layout` without its arguments
+  for source in ["layout;", "uniform layout;", "layout { vec4 v; };"] {
+    let vs = parse(&format!("#version 450\n{}\nvoid main(void) {{}}", source), Stage::Vertex);
+    let messages: Vec<_> = vs.diagnostics.iter().map(|diagnostic| (diagnostic.line, diagnostic.message.as_str())).collect();
+    assert_eq!(messages, [(Some(2), "expected `(...)` after `layout`")], "{}", source);
+  }
+
+  // Lines follow the preprocessor's `#line`s
+  let included = parse("#version 430\n#line 1 1\nuniform float x\n#line 3 0\n", Stage::Compute);
+  assert_eq!(included.diagnostics[0].line, Some(1));
+  assert_eq!(included.diagnostics[0].file, None);
+
+  let zero = parse("#version 430\n#line 0\nuniform float x\n", Stage::Compute);
+  assert!(zero.has_errors());
+}
+
+#[test]
+fn stage_interfaces() {
+  let vs = parse("#version 410\nout VS_OUT { vec3 normal; vec2 tc; } vs_out;\nout vec4 color;", Stage::Vertex);
+  let gs = parse("#version 410\nin VS_OUT { vec3 normal; } gs_in[];\nin vec4 color[];", Stage::Geometry);
+  assert!(check_stages((Stage::Vertex, &vs), (Stage::Geometry, &gs)).is_ok());
+
+  let fs = parse("#version 410\nin VS_OUT { vec4 normal; } fs_in;", Stage::Fragment);
+  let err = check_stages((Stage::Vertex, &vs), (Stage::Fragment, &fs)).unwrap_err();
+  assert_eq!(err.to_string(), "vertex shader output `VS_OUT.normal` is vec3 but fragment shader input `VS_OUT.normal` is vec4");
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use sb7::include::{IncludeErr, Preprocessor, Source};
+
+fn write_tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("sb7_include_{}_{}", name, std::process::id()));
+  for (path, src) in files {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, src).unwrap();
+  }
+  dir
+}
+
+#[test]
+fn expand() {
+  let dir = write_tree("expand", &[
+    ("main.fs.glsl", "#version 450 core\n#include \"common.glsl\"\n#include <lib/noise.glsl>\nvoid main() {}\n"),
+    ("common.glsl", "#pragma once\nfloat common;\n"),
+    ("inc/lib/noise.glsl", "#include \"../../common.glsl\"\nfloat noise;\n"),
+  ]);
+
+  let source = Preprocessor::new(vec![dir.join("inc")]).load(&dir.join("main.fs.glsl")).unwrap();
+  assert_eq!(source.files, [dir.join("main.fs.glsl"), dir.join("common.glsl"), dir.join("inc/lib/noise.glsl")]);
+  assert_eq!(source.text, "#version 450 core\n\
+                           #line 1 1\n\nfloat common;\n#line 3 0\n\
+                           #line 1 2\n\nfloat noise;\n#line 4 0\n\
+                           void main() {}\n");
+
+  // Mesa, NVIDIA and AMD style locations
+  assert_eq!(source.map_log("2:2(7): error: `noise' redeclared"),
+             format!("{}:2(7): error: `noise' redeclared", dir.join("inc/lib/noise.glsl").display()));
+  assert_eq!(source.map_log("1(2) : error C1008: undefined variable"),
+             format!("{}:2 : error C1008: undefined variable", dir.join("common.glsl").display()));
+  assert_eq!(source.map_log("ERROR: 0:4: syntax error"),
+             format!("ERROR: {}:4: syntax error", dir.join("main.fs.glsl").display()));
+  assert_eq!(source.map_log("9:1: unknown string"), "9:1: unknown string");
+
+  std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn errors() {
+  let dir = write_tree("errors", &[
+    ("missing.glsl", "\n#include \"nope.glsl\"\n"),
+    ("a.glsl", "#include \"b.glsl\"\n"),
+    ("b.glsl", "#include \"a.glsl\"\n"),
+    ("syntax.glsl", "#include nope.glsl\n"),
+  ]);
+  let preprocessor = Preprocessor::default();
+
+  assert!(matches!(preprocessor.load(&dir.join("missing.glsl")), Err(IncludeErr::NotFoundErr(_, 2, _))));
+  assert!(matches!(preprocessor.load(&dir.join("a.glsl")), Err(IncludeErr::RecursiveErr(_, 1, _))));
+  assert!(matches!(preprocessor.load(&dir.join("syntax.glsl")), Err(IncludeErr::SyntaxErr(_, 1))));
+  assert!(matches!(preprocessor.load(&dir.join("none.glsl")), Err(IncludeErr::IoErr(..))));
+
+  std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn defines() {
+  let mut source = Source { text: "// tonemap\n#version 450 core\nvoid main() {}\n".into(), files: vec!["tonemap.fs.glsl".into()] };
+  source.define(&[("ADAPTIVE", ""), ("SAMPLES", "25")]);
+  assert_eq!(source.text, "// tonemap\n#version 450 core\n#define ADAPTIVE\n#define SAMPLES 25\n#line 3 0\nvoid main() {}\n");
+
+  let mut source = Source { text: "void main() {}".into(), files: Vec::new() };
+  source.define(&[("A", "1")]);
+  assert_eq!(source.text, "#define A 1\n#line 1 0\nvoid main() {}");
+
+  // Without a trailing newline after the version
+  let mut source = Source { text: "#version 450".into(), files: Vec::new() };
+  source.define(&[("A", "1")]);
+  assert_eq!(source.text, "#version 450\n#define A 1\n#line 2 0\n");
+}
@@ -0,0 +1,252 @@
+use sb7::ktx;
+use sb7::ktx::file::{KeyValues, Orientation, OpenErr};
+
+fn ktx_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+  for entry in std::fs::read_dir(dir).unwrap().flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      ktx_files(&path, files);
+    } else if path.extension().is_some_and(|ext| ext == "ktx") {
+      files.push(path);
+    }
+  }
+}
+
+#[test]
+fn media_textures() {
+  let mut files = Vec::new();
+  ktx_files("media/textures".as_ref(), &mut files);
+  assert!(!files.is_empty());
+
+  for path in files {
+    let image = ktx::parse(std::fs::File::open(&path).unwrap()).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+
+    // The mipmapped tunnel textures are tightly packed, the rest pad rows to 4
+    let name = path.file_stem().unwrap().to_str().unwrap();
+    let alignment = if matches!(name, "brick" | "ceiling" | "floor") { 1 } else { 4 };
+    assert_eq!(image.alignment, alignment, "{}", path.display());
+
+    let channels = match image.header.glformat {
+      gl::RED => 1,
+      gl::RG => 2,
+      gl::RGB | gl::BGR => 3,
+      gl::RGBA | gl::BGRA => 4,
+      format => panic!("{}: unexpected format {:#x}", path.display(), format),
+    };
+    for level in 0..image.levels() {
+      let [w, h, d] = image.extent(level);
+      let row = (w * channels * image.header.gltypesize).next_multiple_of(alignment);
+      for layer in 0..image.layers() {
+        for face in 0..image.faces() {
+          let len = image.image(level, layer, face).len();
+          assert_eq!(len as u32, row * h.max(1) * d.max(1), "{} level {}", path.display(), level);
+        }
+      }
+    }
+  }
+
+  let brick = ktx::parse(std::fs::File::open("media/textures/brick.ktx").unwrap()).unwrap();
+  assert_eq!((brick.target, brick.levels(), brick.extent(9)), (gl::TEXTURE_2D, 10, [1, 1, 0]));
+  assert_eq!(brick.level(1).len(), 256 * 256 * 3);
+
+  let array = ktx::parse(std::fs::File::open("media/textures/cp437_9x16.ktx").unwrap()).unwrap();
+  assert_eq!((array.target, array.layers(), array.alignment), (gl::TEXTURE_2D_ARRAY, 256, 4));
+  assert_eq!(array.image(0, 255, 0).len(), 12 * 16);
+}
+
+fn header(width: u32, height: u32, faces: u32, levels: u32) -> Vec<u8> {
+  let mut bytes = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+  for n in [0x04030201, gl::UNSIGNED_BYTE, 1, gl::RED, gl::R8, gl::RED, width, height, 0, 0, faces, levels, 0] {
+    bytes.extend(u32::to_le_bytes(n));
+  }
+  bytes
+}
+
+#[test]
+fn spec_layout() {
+  // A 3x2 cube map with two levels, imageSize per level and padded rows
+  let mut bytes = header(3, 2, 6, 2);
+  for (level, size) in [(0u8, 8u32), (1, 4)] {
+    bytes.extend(size.to_le_bytes());
+    for face in 0..6 {
+      bytes.extend(std::iter::repeat_n(level * 10 + face, size as usize));
+    }
+  }
+
+  let image = ktx::parse(&bytes[..]).unwrap();
+  assert_eq!((image.target, image.faces(), image.levels()), (gl::TEXTURE_CUBE_MAP, 6, 2));
+  assert_eq!(image.image(0, 0, 5), [5; 8]);
+  assert_eq!(image.image(1, 0, 2), [12; 4]);
+
+  // Truncated data
+  assert!(matches!(ktx::parse(&bytes[..bytes.len() - 30]), Err(OpenErr::DataErr)));
+  assert!(matches!(ktx::parse(&bytes[..20]), Err(OpenErr::IoErr(_))));
+
+  // A format that can't be sized, packed without imageSize
+  let mut bytes = header(4, 4, 0, 1);
+  bytes[24..28].copy_from_slice(&0u32.to_le_bytes());
+  bytes[32..36].copy_from_slice(&0u32.to_le_bytes());
+  bytes.extend([0; 16]);
+  assert!(matches!(ktx::parse(&bytes[..]), Err(OpenErr::DataErr)));
+}
+
+#[test]
+fn malformed_headers() {
+  let set = |bytes: &mut Vec<u8>, offset: usize, n: u32| bytes[offset..offset + 4].copy_from_slice(&n.to_le_bytes());
+  let parse = |bytes: &Vec<u8>| ktx::parse(&bytes[..]);
+
+  // One face is a plain 2D texture, only 6 make a cube map
+  let mut bytes = header(1, 1, 1, 1);
+  bytes.extend([1, 0, 0, 0, 0xff, 0, 0, 0]);
+  assert_eq!(parse(&bytes).unwrap().target, gl::TEXTURE_2D);
+  set(&mut bytes, 52, 2);
+  assert!(matches!(parse(&bytes), Err(OpenErr::HeaderErr)));
+
+  // More levels than the largest dimension has
+  let mut bytes = header(4, 2, 0, 3);
+  bytes.extend([0; 64]);
+  assert!(parse(&bytes).is_ok());
+  set(&mut bytes, 56, 4);
+  assert!(matches!(parse(&bytes), Err(OpenErr::HeaderErr)));
+  set(&mut bytes, 56, 33);
+  assert!(matches!(parse(&bytes), Err(OpenErr::HeaderErr)));
+
+  // Layers times faces overflowing
+  let mut bytes = header(1, 1, 6, 1);
+  set(&mut bytes, 48, u32::MAX);
+  assert!(matches!(parse(&bytes), Err(OpenErr::HeaderErr)));
+
+  // Images too large to size
+  let mut bytes = header(u32::MAX, u32::MAX, 0, 1);
+  bytes.extend([0; 16]);
+  assert!(matches!(parse(&bytes), Err(OpenErr::HeaderErr)));
+
+  // Many empty images don't make a layout
+  let mut bytes = header(1, 1, 0, 1);
+  set(&mut bytes, 48, 1 << 30);
+  bytes.extend([0; 8]);
+  assert!(matches!(parse(&bytes), Err(OpenErr::DataErr)));
+
+  // Key/value data past the end of the file
+  let mut bytes = header(1, 1, 0, 1);
+  set(&mut bytes, 60, u32::MAX);
+  bytes.extend([0; 8]);
+  assert!(matches!(parse(&bytes), Err(OpenErr::IoErr(_))));
+}
+
+fn pair(key: &str, value: &[u8], big_endian: bool) -> Vec<u8> {
+  let size = (key.len() + 1 + value.len()) as u32;
+  let mut bytes = if big_endian { size.to_be_bytes() } else { size.to_le_bytes() }.to_vec();
+  bytes.extend(key.as_bytes());
+  bytes.push(0);
+  bytes.extend(value);
+  bytes.resize((bytes.len() + 3) & !3, 0);
+  bytes
+}
+
+#[test]
+fn key_values() {
+  let mut data = pair("KTXorientation", b"S=r,T=d\0", false);
+  data.extend(pair("sb7.noise.seed", &[1, 2, 3], false));
+  let key_values = KeyValues::parse(&data, false).unwrap();
+  assert_eq!(key_values.get_str("KTXorientation"), Some("S=r,T=d"));
+  assert_eq!(key_values.get("sb7.noise.seed"), Some(&[1u8, 2, 3][..]));
+  assert_eq!(key_values.orientation(), Some(Orientation { s_right: true, t_down: true, r_in: None }));
+  assert!(key_values.orientation().unwrap().flip_v());
+
+  let swapped = pair("KTXorientation", b"S=l,T=u,R=i\0", true);
+  let orientation = KeyValues::parse(&swapped, true).unwrap().orientation().unwrap();
+  assert_eq!(orientation.to_string(), "S=l,T=u,R=i");
+  assert!(!orientation.flip_v());
+
+  assert_eq!(Orientation::parse("S=r"), None);
+  assert_eq!(Orientation::parse("S=r,T=x"), None);
+  assert!(matches!(KeyValues::parse(&data[..data.len() - 4], false), Err(OpenErr::KeyValueErr)));
+
+  // Through a whole file
+  let mut bytes = header(1, 1, 0, 1);
+  bytes[60..64].copy_from_slice(&(data.len() as u32).to_le_bytes());
+  bytes.extend(&data);
+  bytes.extend([1, 0, 0, 0, 0xff, 0, 0, 0]);
+  let image = ktx::parse(&bytes[..]).unwrap();
+  assert_eq!(image.key_values, key_values);
+  assert_eq!(image.image(0, 0, 0), [0xff]);
+}
+
+fn round_trip(image: &ktx::KtxImage) -> ktx::KtxImage {
+  let mut bytes = Vec::new();
+  image.write(&mut bytes).unwrap();
+  ktx::parse(bytes.as_slice()).unwrap()
+}
+
+#[test]
+fn write() {
+  let mut array = ktx::KtxImage::new(gl::TEXTURE_2D_ARRAY, gl::RGB8, gl::RGB, gl::UNSIGNED_BYTE, [5, 3, 0], 2, 3);
+  array.key_values.set_orientation(Orientation { s_right: true, t_down: false, r_in: None });
+  for level in 0..3 {
+    for layer in 0..2 {
+      for (i, byte) in array.image_mut(level, layer, 0).iter_mut().enumerate() {
+        *byte = (i as u32 + level * 7 + layer * 13) as u8;
+      }
+    }
+  }
+  // 5 RGB texels take 15 bytes, padded to 16
+  assert_eq!(array.image(0, 1, 0).len(), 16 * 3);
+
+  let read = round_trip(&array);
+  assert_eq!((read.target, read.levels(), read.layers(), read.alignment), (gl::TEXTURE_2D_ARRAY, 3, 2, 4));
+  assert_eq!((array.header.faces, read.header.faces), (1, 1));
+  assert_eq!(read.key_values.orientation(), array.key_values.orientation());
+  for level in 0..3 {
+    assert_eq!(read.extent(level), array.extent(level));
+    assert_eq!(read.level(level), array.level(level));
+  }
+
+  // A file that gives one face for a 2D texture
+  let mut bytes = header(2, 1, 1, 1);
+  bytes.extend([4, 0, 0, 0, 7, 8, 0, 0]);
+  let read = round_trip(&ktx::parse(&bytes[..]).unwrap());
+  assert_eq!((read.target, read.header.faces, read.image(0, 0, 0)), (gl::TEXTURE_2D, 1, &[7, 8, 0, 0][..]));
+
+  let mut cube = ktx::KtxImage::new(gl::TEXTURE_CUBE_MAP, gl::R8, gl::RED, gl::UNSIGNED_BYTE, [2, 2, 0], 0, 2);
+  for face in 0..6 {
+    cube.image_mut(1, 0, face)[0] = face as u8 + 1;
+  }
+  let read = round_trip(&cube);
+  assert_eq!((read.target, read.faces(), read.levels()), (gl::TEXTURE_CUBE_MAP, 6, 2));
+  for face in 0..6 {
+    assert_eq!(read.image(1, 0, face)[0], face as u8 + 1);
+  }
+}
+
+#[test]
+fn write_media() {
+  // brick.ktx is packed with 1 byte rows, written back with 4 byte ones
+  let brick = ktx::parse(std::fs::File::open("media/textures/brick.ktx").unwrap()).unwrap();
+  let read = round_trip(&brick);
+  assert_eq!((read.levels(), read.alignment), (brick.levels(), 4));
+  assert_eq!(read.level(0), brick.level(0));
+  assert_eq!(read.level(9)[..3], brick.level(9)[..3]);
+}
+
+#[test]
+fn write_formats() {
+  // Packed types hold a whole texel in one element
+  let packed = ktx::KtxImage::new(gl::TEXTURE_2D, gl::R11F_G11F_B10F, gl::RGB, gl::UNSIGNED_INT_10F_11F_11F_REV, [3, 2, 0], 0, 1);
+  assert_eq!((packed.header.gltypesize, packed.image(0, 0, 0).len()), (4, 3 * 4 * 2));
+
+  let integer = ktx::KtxImage::new(gl::TEXTURE_2D, gl::R16UI, gl::RED_INTEGER, gl::UNSIGNED_SHORT, [3, 2, 0], 0, 1);
+  assert_eq!((integer.header.glbaseinternalformat, integer.image(0, 0, 0).len()), (gl::RED, 8 * 2));
+
+  let depth = ktx::KtxImage::new(gl::TEXTURE_2D, gl::DEPTH32F_STENCIL8, gl::DEPTH_STENCIL, gl::FLOAT_32_UNSIGNED_INT_24_8_REV, [2, 2, 0], 0, 1);
+  assert_eq!(depth.image(0, 0, 0).len(), 2 * 8 * 2);
+
+  let bgra = ktx::KtxImage::new(gl::TEXTURE_2D, gl::RGBA8, gl::BGRA, gl::UNSIGNED_BYTE, [1, 1, 0], 0, 1);
+  assert_eq!(bgra.header.glbaseinternalformat, gl::RGBA);
+
+  for image in [packed, integer, depth, bgra] {
+    let read = round_trip(&image);
+    assert_eq!((read.header.glformat, read.header.gltype), (image.header.glformat, image.header.gltype));
+    assert_eq!(read.level(0), image.level(0));
+  }
+}
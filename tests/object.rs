@@ -0,0 +1,43 @@
+use sb7::object::{LoadErr, Object};
+
+// Chunk list of torus.sbm: ATRB at 16 (size 180), VRTX at 196 (size 20)
+fn load_patched(name: &str, patch: impl FnOnce(&mut Vec<u8>)) -> Result<(), LoadErr> {
+  let mut data = std::fs::read("media/objects/torus.sbm").unwrap();
+  patch(&mut data);
+
+  let path = std::env::temp_dir().join(format!("sb7_object_{}.sbm", name));
+  std::fs::write(&path, data).unwrap();
+  let result = Object::default().try_load(path.to_str().unwrap());
+  std::fs::remove_file(path).unwrap();
+  result
+}
+
+fn set_u32(data: &mut [u8], offset: usize, value: u32) {
+  data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn truncated() {
+  assert!(matches!(load_patched("header", |data| data.truncate(16)), Err(LoadErr::HeaderErr)));
+
+  for len in [20, 100, 196, 200, 215, 1000] {
+    let result = load_patched(&format!("truncated_{}", len), |data| data.truncate(len));
+    assert!(matches!(result, Err(LoadErr::ChunkErr)), "{}", len);
+  }
+}
+
+#[test]
+fn out_of_bounds() {
+  let patches = [
+    ("chunk_size",    200, 0xffff_ff00),
+    ("empty_chunk",   200, 0),
+    ("attrib_count",  24,  1000),
+    ("vertex_size",   204, 64681),
+    ("vertex_offset", 208, u32::MAX),
+  ];
+
+  for (name, offset, value) in patches {
+    let result = load_patched(name, |data| set_u32(data, offset, value));
+    assert!(matches!(result, Err(LoadErr::ChunkErr)), "{}", name);
+  }
+}
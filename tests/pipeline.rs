@@ -0,0 +1,32 @@
+use sb7::pipeline::{check_interface, PipelineErr};
+use sb7::reflect::{GlslType, Variable};
+use sb7::shader::Stage;
+
+fn var(name: &str, kind: u32, location: i32) -> Variable {
+  Variable { name: name.into(), kind: GlslType(kind), location, array_size: 1 }
+}
+
+#[test]
+fn matching_interfaces() {
+  let outputs = [var("gl_Position", gl::FLOAT_VEC4, -1), var("VS_OUT.color", gl::FLOAT_VEC4, 0), var("uv", gl::FLOAT_VEC2, 1)];
+
+  // Arrayed geometry inputs, built-ins and unused outputs are fine
+  let inputs = [var("gl_PrimitiveIDIn", gl::INT, -1), var("VS_OUT.color[0]", gl::FLOAT_VEC4, 0)];
+  assert!(check_interface(Stage::Vertex, &outputs, Stage::Geometry, &inputs).is_ok());
+
+  // Different names, same explicit location
+  let inputs = [var("texcoord", gl::FLOAT_VEC2, 1)];
+  assert!(check_interface(Stage::Vertex, &outputs, Stage::Fragment, &inputs).is_ok());
+}
+
+#[test]
+fn mismatched_interfaces() {
+  let outputs = [var("color", gl::FLOAT_VEC3, -1)];
+
+  let err = check_interface(Stage::Vertex, &outputs, Stage::Fragment, &[var("normal", gl::FLOAT_VEC3, -1)]).unwrap_err();
+  assert!(matches!(err, PipelineErr::MissingOutputErr(Stage::Vertex, Stage::Fragment, _)));
+  assert_eq!(err.to_string(), "fragment shader input `normal` (vec3) is not written by the vertex shader");
+
+  let err = check_interface(Stage::Vertex, &outputs, Stage::Fragment, &[var("color", gl::FLOAT_VEC4, -1)]).unwrap_err();
+  assert_eq!(err.to_string(), "vertex shader output `color` is vec3 but fragment shader input `color` is vec4");
+}
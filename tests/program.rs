@@ -0,0 +1,38 @@
+use sb7::program::{find_stages, Builder, FeedbackMode, Variants};
+use sb7::shader::{ShaderError, Stage};
+
+#[test]
+fn stages_by_suffix() {
+  let dir = std::env::temp_dir().join(format!("sb7_find_stages_{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  for file in ["dispmap.fs.glsl", "dispmap.vs.glsl", "dispmap.tes.glsl", "dispmap.tcs.glsl", "other.gs.glsl"] {
+    std::fs::write(dir.join(file), "").unwrap();
+  }
+
+  let dir_str = dir.to_str().unwrap();
+  let stages: Vec<_> = find_stages(dir_str, "dispmap").into_iter().map(|(_, stage)| stage).collect();
+  assert_eq!(stages, [Stage::Vertex, Stage::TessControl, Stage::TessEvaluation, Stage::Fragment]);
+  assert_eq!(find_stages(dir_str, "dispmap")[0].0, format!("{}/dispmap.vs.glsl", dir_str));
+  assert!(find_stages(dir_str, "missing").is_empty());
+
+  std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn builder_reads_sources_first() {
+  // Missing sources are reported before any GL call is made
+  let result = Builder::new().file("media/shaders/missing.vs.glsl", Stage::Vertex)
+                             .transform_feedback(&["tf_position"], FeedbackMode::Separate)
+                             .separable(true)
+                             .build();
+  assert!(matches!(result, Err(ShaderError::SourceErr(_))));
+}
+
+#[test]
+fn duplicate_defines() {
+  // Rejected before anything is loaded or built
+  let mut variants = Variants::new(&[("missing.fs.glsl", Stage::Fragment)]);
+  let err = variants.get(&[("MODE", "1"), ("SHADOWS", ""), ("MODE", "2")]).unwrap_err();
+  assert!(matches!(&err, ShaderError::DuplicateDefineErr(name) if name == "MODE"));
+  assert_eq!(err.to_string(), "`MODE` is defined more than once");
+}
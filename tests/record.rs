@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use sb7::application::{AppConfig, Application, RunErr};
+use sb7::config::Overrides;
+use sb7::record::{y4m_frame, y4m_header, RecordOutput, Recording};
+use sb7::screenshot::{Image, ImageFormat, Pixels};
+
+#[test]
+fn output_from_path() {
+  assert_eq!(RecordOutput::from_path(Path::new("turntable.y4m")), RecordOutput::Y4m("turntable.y4m".into()));
+  assert_eq!(RecordOutput::from_path(Path::new("frames")),
+             RecordOutput::Sequence { dir: PathBuf::from("frames"), format: ImageFormat::Png });
+}
+
+#[test]
+fn y4m() {
+  assert_eq!(y4m_header(320, 240, 30), "YUV4MPEG2 W320 H240 F30:1 Ip A1:1 C444\n");
+
+  let image = Image { width: 2, height: 1, pixels: Pixels::U8(vec![0, 0, 0, 255, 255, 255]) };
+  let frame = y4m_frame(&image);
+  assert_eq!(&frame[..6], b"FRAME\n");
+  // Planar Y, U, V with studio range black and white
+  assert_eq!(&frame[6..], &[16, 235, 128, 128, 128, 128]);
+}
+
+struct ZeroFps;
+
+impl Application for ZeroFps {
+  fn init(&self) -> AppConfig {
+    let mut info = AppConfig { record: Some(Recording { output: RecordOutput::from_path(Path::new("zero.y4m")), fps: 0 }),
+                               ..AppConfig::default() };
+    info.flags.headless = true;
+    info
+  }
+}
+
+#[test]
+fn zero_fps() {
+  // Rejected before a context is created
+  assert!(matches!(ZeroFps.try_run_with(&Overrides::default()), Err(RunErr::RecordFpsErr)));
+}
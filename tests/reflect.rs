@@ -0,0 +1,59 @@
+use std::error::Error;
+
+use sb7::application::{AppConfig, AppContext, Application, RunErr};
+use sb7::config::Overrides;
+use sb7::program;
+use sb7::reflect::{reflect, GlslType};
+use sb7::shader::{self, Stage};
+
+#[test]
+fn type_names() {
+  assert_eq!(GlslType(gl::FLOAT_VEC3).to_string(), "vec3");
+  assert_eq!(GlslType(gl::FLOAT_MAT4x3).to_string(), "mat4x3");
+  assert_eq!(GlslType(gl::UNSIGNED_INT_SAMPLER_2D).to_string(), "usampler2D");
+  assert_eq!(GlslType(0).to_string(), "unknown_type");
+}
+
+/// Reflects a compute and a render program, neither using any resources.
+#[derive(Default)]
+struct Workgroups {
+  compute: Option<[i32; 3]>,
+  render:  Option<[i32; 3]>,
+}
+
+impl Application for Workgroups {
+  fn init(&self) -> AppConfig {
+    let mut info = AppConfig::default();
+    info.flags.headless = true;
+    info
+  }
+
+  fn try_startup(&mut self, _context: &mut AppContext) -> Result<(), Box<dyn Error>> {
+    let compute = program::try_link(&[
+      shader::try_from_str("#version 430 core\nlayout (local_size_x = 4, local_size_y = 2) in;\nvoid main() {}\n",
+                           Stage::Compute)?,
+    ])?;
+    let render = program::try_link(&[
+      shader::try_from_str("#version 430 core\nvoid main() { gl_Position = vec4(0.0); }\n", Stage::Vertex)?,
+      shader::try_from_str("#version 430 core\nvoid main() {}\n", Stage::Fragment)?,
+    ])?;
+    self.compute = reflect(compute).workgroup_size;
+    self.render = reflect(render).workgroup_size;
+    Ok(())
+  }
+}
+
+#[test]
+fn workgroup_size() {
+  let mut app = Workgroups::default();
+  let overrides = Overrides { frames: Some(1), ..Overrides::default() };
+  match app.try_run_with(&overrides) {
+    // Nothing to check without a context
+    Err(err @ (RunErr::HeadlessErr(_) | RunErr::ContextVersionErr(_))) => eprintln!("skipped: {}", err),
+    result => {
+      result.unwrap();
+      assert_eq!(app.compute, Some([4, 2, 1]));
+      assert_eq!(app.render, None);
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use sb7::screenshot::{encode_hdr, encode_png, encode_ppm, Image, Pixels};
+
+fn image() -> Image {
+  Image {
+    width: 2,
+    height: 2,
+    pixels: Pixels::U8(vec![
+      255, 0, 0,   0, 255, 0,
+      0, 0, 255,   255, 255, 255,
+    ]),
+  }
+}
+
+#[test]
+fn flip_rows() {
+  let mut img = image();
+  img.flip_rows();
+  assert_eq!(img.pixels, Pixels::U8(vec![
+    0, 0, 255,   255, 255, 255,
+    255, 0, 0,   0, 255, 0,
+  ]));
+}
+
+#[test]
+fn ppm() {
+  let ppm = encode_ppm(&image());
+  assert!(ppm.starts_with(b"P6\n2 2\n255\n"));
+  assert_eq!(&ppm[11..], &image().to_u8()[..]);
+}
+
+#[test]
+fn png() {
+  let png = encode_png(&image());
+  assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+  assert_eq!(&png[12..16], b"IHDR");
+  assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+  // IEND with its fixed CRC closes the file
+  assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+}
+
+#[test]
+fn hdr() {
+  let img = Image { width: 1, height: 1, pixels: Pixels::F32(vec![1.0, 0.5, 0.0]) };
+  let hdr = encode_hdr(&img);
+  let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 1\n";
+  assert!(hdr.starts_with(header));
+  assert_eq!(&hdr[header.len()..], &[128, 64, 0, 129]);
+}
@@ -0,0 +1,25 @@
+use sb7::stats::History;
+
+#[test]
+fn rolling_window() {
+  let mut h = History::new(3);
+  assert_eq!(h.average(), None);
+  assert_eq!(h.last(), None);
+
+  for ms in [4.0, 2.0, 6.0, 8.0] {
+    h.push(ms);
+  }
+  assert_eq!(h.samples(), &[2.0, 6.0, 8.0]);
+  assert_eq!(h.last(), Some(8.0));
+  assert_eq!(h.max(), Some(8.0));
+  assert_eq!(h.average(), Some(16.0 / 3.0));
+}
+
+#[test]
+fn zero_capacity() {
+  // Holds one sample rather than none
+  let mut h = History::new(0);
+  h.push(4.0);
+  h.push(2.0);
+  assert_eq!(h.samples(), &[2.0]);
+}